@@ -0,0 +1,113 @@
+//! Encryption of a token's claims to a designated auditor.  The guest
+//! encrypts the claims it has validated, so the receipt proves that the
+//! ciphertext holds the signed values.
+//!
+//! The claims are encrypted with RSA-OAEP (SHA-256) to the auditor's RSA key,
+//! in as many blocks as their length requires.  The blocks need no separate
+//! integrity protection: they are committed to the journal, which the
+//! receipt attests.
+
+use jwt_compact::{
+    alg::{RsaPrivateKey, RsaPublicKey},
+    jwk::JsonWebKey,
+};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::traits::PublicKeyParts;
+use rsa::Oaep;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::{jwk_thumbprint, CustomClaims, Err, ThumbprintHash};
+
+/// Bytes of each RSA block taken up by OAEP padding with SHA-256.
+const OAEP_OVERHEAD: usize = 2 * 32 + 2;
+
+/// Randomness for the OAEP padding, chosen by the prover.
+pub type AuditSeed = [u8; 32];
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct AuditCiphertext {
+    /// RFC 7638 thumbprint of the auditor's key.
+    pub key_fingerprint: String,
+    pub blocks: Vec<Vec<u8>>,
+}
+
+impl AuditCiphertext {
+    /// Encrypts all of the claims, public and private, to `auditor_key`.
+    pub fn encrypt(claims: &CustomClaims, auditor_key: &str, seed: AuditSeed) -> Result<Self, Err> {
+        let key = RsaPublicKey::try_from(&serde_json::from_str::<JsonWebKey>(auditor_key)?)?;
+        let plaintext = serde_json::to_vec(claims)?;
+        let block_len = key
+            .size()
+            .checked_sub(OAEP_OVERHEAD)
+            .filter(|len| *len > 0)
+            .ok_or_else(|| Err::AuditEncryptionFailed("auditor key is too short".to_string()))?;
+
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let blocks = plaintext
+            .chunks(block_len)
+            .map(|block| {
+                key.encrypt(&mut rng, Oaep::new::<Sha256>(), block)
+                    .map_err(|e| Err::AuditEncryptionFailed(e.to_string()))
+            })
+            .collect::<Result<Vec<Vec<u8>>, Err>>()?;
+
+        Ok(AuditCiphertext {
+            key_fingerprint: jwk_thumbprint(auditor_key, ThumbprintHash::Sha256)?,
+            blocks,
+        })
+    }
+
+    /// Recovers the claims with the auditor's secret JWK.
+    pub fn decrypt(&self, secret_key: &str) -> Result<CustomClaims, Err> {
+        if jwk_thumbprint(secret_key, ThumbprintHash::Sha256)? != self.key_fingerprint {
+            return Err(Err::AuditKeyMismatch(self.key_fingerprint.clone()));
+        }
+        let key = RsaPrivateKey::try_from(&serde_json::from_str::<JsonWebKey>(secret_key)?)?;
+
+        let mut plaintext = Vec::new();
+        for block in self.blocks.iter() {
+            let block = key
+                .decrypt(Oaep::new::<Sha256>(), block)
+                .map_err(|e| Err::AuditDecryptionFailed(e.to_string()))?;
+            plaintext.extend(block);
+        }
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const SK: &str = include_str!("../../test_data/coffee_company_sk.jwk");
+    const PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+    const OTHER_PK: &str = include_str!("../../test_data/other_pk_1.jwk");
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let mut claims = CustomClaims::new();
+        claims.add("issuer_id".to_string(), "Coffee Chain 1".to_string(), true);
+        // Long enough to need more than one block.
+        claims.add("notes".to_string(), "x".repeat(1000), true);
+
+        let ciphertext = AuditCiphertext::encrypt(&claims, PK, [7; 32]).unwrap();
+        assert!(ciphertext.blocks.len() > 1);
+        assert_eq!(
+            AuditCiphertext::encrypt(&claims, PK, [7; 32]).unwrap(),
+            ciphertext
+        );
+
+        let decrypted = ciphertext.decrypt(SK).unwrap();
+        assert_eq!(decrypted.get("issuer_id"), Some("Coffee Chain 1"));
+        assert_eq!(decrypted.get("notes"), claims.get("notes"));
+
+        let ciphertext = AuditCiphertext::encrypt(&claims, OTHER_PK, [7; 32]).unwrap();
+        assert!(matches!(
+            ciphertext.decrypt(SK),
+            Err(Err::AuditKeyMismatch(_))
+        ));
+    }
+}
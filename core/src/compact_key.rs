@@ -0,0 +1,191 @@
+//! Binary encoding of candidate keys for the guest.
+//!
+//! The host parses each JWK once and hands the guest the big-endian bytes of
+//! the RSA modulus and exponent, so the guest does not have to parse JSON or
+//! decode base64 before it can check a signature.  The JWK's `use`,
+//! `key_ops`, `alg` and `kid` members travel alongside, so that the guest
+//! checks them too and commits them in the canonical JWKs it turns the keys
+//! back into.
+//!
+//! The key set must be canonical: sorted by RFC 7638 thumbprint with no key
+//! repeated, so that a prover cannot pad the set with copies of a key.
+
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use jwt_compact::alg::{Rsa, RsaPublicKey};
+use jwt_compact::jwk::JsonWebKey;
+use rsa::traits::PublicKeyParts;
+use rsa::BigUint;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{rsa_thumbprint, Err, JwkMetadata, KeyValidity, Validator};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct CompactKey {
+    pub n: Vec<u8>,
+    pub e: Vec<u8>,
+    pub validity: KeyValidity,
+    /// What the key may be used for, and the `kid` tokens may name it by.
+    pub metadata: JwkMetadata,
+}
+
+impl CompactKey {
+    /// Encodes a JWK, which must allow verifying RS256 signatures.
+    pub fn from_jwk(jwk_str: &str) -> Result<Self, Err> {
+        let metadata = JwkMetadata::from_jwk(jwk_str)?;
+        metadata.check()?;
+        let jwk = serde_json::from_str::<JsonWebKey>(jwk_str)?;
+        let p_k = RsaPublicKey::try_from(&jwk)?;
+        Ok(CompactKey {
+            n: p_k.n().to_bytes_be(),
+            e: p_k.e().to_bytes_be(),
+            validity: KeyValidity::from_jwk(jwk_str)?,
+            metadata,
+        })
+    }
+
+    /// The key as a JWK with only the members needed to verify with it:
+    /// `kty`, `n`, `e`, its `use`, `key_ops`, `alg` and `kid` and its
+    /// validity window, if any.
+    pub fn to_jwk(&self) -> String {
+        let mut members = Map::new();
+        if let Some(alg) = &self.metadata.alg {
+            members.insert("alg".to_string(), alg.clone().into());
+        }
+        members.insert(
+            "e".to_string(),
+            BASE64_URL_SAFE_NO_PAD.encode(&self.e).into(),
+        );
+        if let Some(key_ops) = &self.metadata.key_ops {
+            members.insert("key_ops".to_string(), key_ops.clone().into());
+        }
+        if let Some(kid) = &self.metadata.kid {
+            members.insert("kid".to_string(), kid.clone().into());
+        }
+        members.insert("kty".to_string(), "RSA".into());
+        members.insert(
+            "n".to_string(),
+            BASE64_URL_SAFE_NO_PAD.encode(&self.n).into(),
+        );
+        for (member, timestamp) in [
+            ("not_before", self.validity.not_before),
+            ("not_after", self.validity.not_after),
+        ] {
+            if let Some(timestamp) = timestamp {
+                members.insert(member.to_string(), timestamp.into());
+            }
+        }
+        if let Some(public_key_use) = &self.metadata.public_key_use {
+            members.insert("use".to_string(), public_key_use.clone().into());
+        }
+        Value::Object(members).to_string()
+    }
+
+    /// RFC 7638 thumbprint of the key with SHA-256, as for its JWK.
+    pub fn thumbprint(&self) -> String {
+        rsa_thumbprint(&self.n, &self.e)
+    }
+
+    pub fn validator(&self) -> Result<Validator, Err> {
+        let p_k = RsaPublicKey::new(
+            BigUint::from_bytes_be(&self.n),
+            BigUint::from_bytes_be(&self.e),
+        )
+        .map_err(|e| Err::InvalidKey(e.to_string()))?;
+        Ok(Validator {
+            alg: Rsa::rs256(),
+            p_k,
+            validity: self.validity,
+            kid: self.metadata.kid.clone(),
+            thumbprint: self.thumbprint(),
+        })
+    }
+}
+
+/// Checks that every key is a usable RSA public key that may verify RS256
+/// signatures and that the keys are in ascending order of thumbprint, which
+/// rules out repeated keys.
+pub fn check_key_set(keys: &[CompactKey]) -> Result<(), Err> {
+    let mut previous: Option<String> = None;
+    for key in keys.iter() {
+        key.metadata.check()?;
+        key.validator()?;
+        let thumbprint = key.thumbprint();
+        if previous.is_some_and(|previous| previous >= thumbprint) {
+            return Err(Err::KeySetNotCanonical);
+        }
+        previous = Some(thumbprint);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{jwk_thumbprint, ThumbprintHash};
+
+    const PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+    const OTHER_PK: &str = include_str!("../../test_data/other_pk_2.jwk");
+
+    #[test]
+    fn test_compact_key_round_trip() {
+        let key = CompactKey::from_jwk(PK).unwrap();
+        assert_eq!(key.n.len(), 384);
+        assert_eq!(key.e, vec![1, 0, 1]);
+
+        let jwk = key.to_jwk();
+        assert_eq!(
+            jwk_thumbprint(&jwk, ThumbprintHash::Sha256).unwrap(),
+            jwk_thumbprint(PK, ThumbprintHash::Sha256).unwrap()
+        );
+        assert_eq!(CompactKey::from_jwk(&jwk).unwrap(), key);
+        assert_eq!(
+            key.metadata.to_string(),
+            "kid 6ab0e8e4bc121fc287e35d3e5e0efb8a, use sig, key_ops verify, alg RS256"
+        );
+        let members: Value = serde_json::from_str(&jwk).unwrap();
+        assert_eq!(members["use"], "sig");
+        assert_eq!(members["key_ops"][0], "verify");
+        assert_eq!(members["alg"], "RS256");
+        assert_eq!(
+            key.validator().unwrap().p_k,
+            PK.parse::<Validator>().unwrap().p_k
+        );
+        assert_eq!(
+            key.thumbprint(),
+            jwk_thumbprint(PK, ThumbprintHash::Sha256).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_key_set() {
+        let key = CompactKey::from_jwk(PK).unwrap();
+        let other = CompactKey::from_jwk(OTHER_PK).unwrap();
+        let mut keys = vec![key.clone(), other];
+        keys.sort_by_key(CompactKey::thumbprint);
+        assert!(check_key_set(&keys).is_ok());
+
+        keys.reverse();
+        assert!(matches!(check_key_set(&keys), Err(Err::KeySetNotCanonical)));
+        assert!(matches!(
+            check_key_set(&[key.clone(), key.clone()]),
+            Err(Err::KeySetNotCanonical)
+        ));
+
+        let for_encryption = CompactKey {
+            metadata: JwkMetadata {
+                public_key_use: Some("enc".to_string()),
+                ..key.metadata.clone()
+            },
+            ..key.clone()
+        };
+        assert!(matches!(
+            check_key_set(&[for_encryption]),
+            Err(Err::KeyNotForVerification(_))
+        ));
+
+        let junk = CompactKey { n: vec![7], ..key };
+        assert!(matches!(check_key_set(&[junk]), Err(Err::InvalidKey(_))));
+    }
+}
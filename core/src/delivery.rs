@@ -0,0 +1,178 @@
+//! Delivery receipts signed by a carrier or warehouse, which confirm that the
+//! goods on an invoice arrived.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{CustomClaims, Err, Validator};
+
+/// Claim shared by an invoice and the delivery receipt for its goods.
+pub const REFERENCE_CLAIM: &str = "reference";
+
+/// Claim holding the outcome of the delivery, which must be `delivered`.
+pub const STATUS_CLAIM: &str = "status";
+
+pub const DELIVERED: &str = "delivered";
+
+/// Media type set in the header of delivery receipts, so that an invoice
+/// cannot be passed off as one.
+pub const DELIVERY_RECEIPT_TYPE: &str = "delivery+jwt";
+
+/// A delivery receipt token and the candidate keys of the carriers that may
+/// have signed it.  The token is a private input; the keys are public.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct DeliveryReceipt {
+    pub token: String,
+    pub public_keys: Vec<String>,
+}
+
+/// The public part of a delivery receipt that matched an invoice.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DeliveryConfirmation {
+    pub pks: Vec<String>,
+    pub claims: CustomClaims,
+}
+
+impl DeliveryReceipt {
+    /// Validates the receipt against its own key set and checks that it
+    /// reports the goods as delivered under the same reference as the
+    /// invoice.  The carrier keys must be distinct from the thumbprints of
+    /// the invoice's candidate keys, so that the customer cannot confirm
+    /// their own delivery.
+    pub fn confirm(
+        &self,
+        invoice_token: &str,
+        invoice: &CustomClaims,
+        invoice_key_thumbprints: &[String],
+    ) -> Result<DeliveryConfirmation, Err> {
+        if self.token == invoice_token {
+            return Err(Err::DeliveryReceiptIsInvoice);
+        }
+        let validators = self
+            .public_keys
+            .iter()
+            .map(|pk| pk.parse::<Validator>())
+            .collect::<Result<Vec<Validator>, Err>>()?;
+        if let Some(validator) = validators
+            .iter()
+            .find(|validator| invoice_key_thumbprints.contains(&validator.thumbprint))
+        {
+            return Err(Err::CarrierKeyIsInvoiceKey(validator.thumbprint.clone()));
+        }
+
+        let receipt = validators
+            .iter()
+            .find_map(|validator| validator.validate_delivery_receipt(&self.token).ok())
+            .ok_or(Err::DeliveryReceiptNotSigned)?;
+        let claims = &receipt.claims().custom;
+
+        match claims.get(STATUS_CLAIM) {
+            Some(DELIVERED) => {}
+            status => {
+                return Err(Err::DeliveryNotCompleted(
+                    status.unwrap_or_default().to_string(),
+                ))
+            }
+        }
+
+        let reference = invoice
+            .get(REFERENCE_CLAIM)
+            .ok_or_else(|| Err::MissingClaim(REFERENCE_CLAIM.to_string()))?;
+        if claims.get(REFERENCE_CLAIM) != Some(reference) {
+            return Err(Err::ReferenceMismatch);
+        }
+
+        Ok(DeliveryConfirmation {
+            pks: self.public_keys.clone(),
+            claims: claims.get_public_claims(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{jwk_thumbprint, Issuer, ThumbprintHash};
+
+    const SK: &str = include_str!("../../test_data/coffee_company_sk.jwk");
+    const PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+    const OTHER_PK: &str = include_str!("../../test_data/other_pk_1.jwk");
+
+    #[test]
+    fn test_delivery_receipt_must_match_reference() {
+        let iss = SK.parse::<Issuer>().unwrap();
+        let mut invoice = CustomClaims::new();
+        invoice.add("reference".to_string(), "PO-1".to_string(), false);
+        let invoice_token = iss.generate_token(&invoice).unwrap();
+        // The invoice is signed by another customer key.
+        let invoice_keys = vec![jwk_thumbprint(OTHER_PK, ThumbprintHash::Sha256).unwrap()];
+
+        let mut receipt_claims = CustomClaims::new();
+        receipt_claims.add("reference".to_string(), "PO-1".to_string(), false);
+        receipt_claims.add("carrier_id".to_string(), "Haulier".to_string(), true);
+        receipt_claims.add("status".to_string(), "delivered".to_string(), false);
+        let receipt = DeliveryReceipt {
+            token: iss.sign_delivery_receipt(&receipt_claims).unwrap(),
+            public_keys: vec![PK.to_string()],
+        };
+        let confirmation = receipt
+            .confirm(&invoice_token, &invoice, &invoice_keys)
+            .unwrap();
+        assert_eq!(confirmation.claims.get("reference"), Some("PO-1"));
+        assert_eq!(confirmation.claims.get("carrier_id"), None);
+
+        let mut other = CustomClaims::new();
+        other.add("reference".to_string(), "PO-2".to_string(), false);
+        assert!(matches!(
+            receipt.confirm(&invoice_token, &other, &invoice_keys),
+            Err(Err::ReferenceMismatch)
+        ));
+
+        let unsigned = DeliveryReceipt {
+            public_keys: Vec::new(),
+            ..receipt.clone()
+        };
+        assert!(matches!(
+            unsigned.confirm(&invoice_token, &invoice, &invoice_keys),
+            Err(Err::DeliveryReceiptNotSigned)
+        ));
+
+        // The customer's key cannot confirm their own delivery.
+        let customer_keys = vec![PK.parse::<Validator>().unwrap().thumbprint];
+        assert!(matches!(
+            receipt.confirm(&invoice_token, &invoice, &customer_keys),
+            Err(Err::CarrierKeyIsInvoiceKey(_))
+        ));
+
+        // Nor can an invoice, even one with the delivery status, stand in
+        // for a delivery receipt.
+        let invoice_as_receipt = DeliveryReceipt {
+            token: iss.generate_token(&receipt_claims).unwrap(),
+            ..receipt.clone()
+        };
+        assert!(matches!(
+            invoice_as_receipt.confirm(&invoice_token, &invoice, &invoice_keys),
+            Err(Err::DeliveryReceiptNotSigned)
+        ));
+        let same_token = DeliveryReceipt {
+            token: invoice_token.clone(),
+            ..receipt.clone()
+        };
+        assert!(matches!(
+            same_token.confirm(&invoice_token, &invoice, &invoice_keys),
+            Err(Err::DeliveryReceiptIsInvoice)
+        ));
+
+        let mut pending_claims = CustomClaims::new();
+        pending_claims.add("reference".to_string(), "PO-1".to_string(), false);
+        pending_claims.add("status".to_string(), "in transit".to_string(), false);
+        let pending = DeliveryReceipt {
+            token: iss.sign_delivery_receipt(&pending_claims).unwrap(),
+            ..receipt
+        };
+        assert!(matches!(
+            pending.confirm(&invoice_token, &invoice, &invoice_keys),
+            Err(Err::DeliveryNotCompleted(_))
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+//! Validity windows of issuer keys and the rotation records that link a key
+//! to its successor.
+//!
+//! A key in the key set may carry `not_before` and `not_after` members, in
+//! seconds since the Unix epoch, alongside its JWK members.  A token is only
+//! accepted if its `iat` claim lies in the window of the key that signed it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Err;
+
+/// Media type set in the header of key rotation records.
+pub const KEY_ROTATION_TYPE: &str = "key-rotation+jwt";
+
+/// The window in which tokens signed by a key must have been issued: from
+/// `not_before` (inclusive) until `not_after` (exclusive).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyValidity {
+    #[serde(default)]
+    pub not_before: Option<u64>,
+    #[serde(default)]
+    pub not_after: Option<u64>,
+}
+
+impl KeyValidity {
+    /// Reads the validity members of a JWK; other members are ignored.
+    pub fn from_jwk(jwk_str: &str) -> Result<Self, Err> {
+        Ok(serde_json::from_str(jwk_str)?)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.not_before.is_none() && self.not_after.is_none()
+    }
+
+    pub fn contains(&self, timestamp: u64) -> bool {
+        self.not_before
+            .is_none_or(|not_before| not_before <= timestamp)
+            && self.not_after.is_none_or(|not_after| timestamp < not_after)
+    }
+
+    /// Checks the issue time of a token signed by the key.  Tokens without
+    /// an `iat` claim are only accepted by keys without a window.
+    pub fn check(&self, issued_at: Option<DateTime<Utc>>) -> Result<(), Err> {
+        if self.is_unbounded() {
+            return Ok(());
+        }
+        let issued_at = issued_at.ok_or(Err::MissingIssuedAt)?;
+        let timestamp = u64::try_from(issued_at.timestamp()).unwrap_or_default();
+        if !self.contains(timestamp) {
+            return Err(Err::KeyNotValidAt(issued_at.to_rfc3339()));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for KeyValidity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = |timestamp: u64| {
+            DateTime::from_timestamp(timestamp as i64, 0)
+                .map(|time| time.to_rfc3339())
+                .unwrap_or_else(|| timestamp.to_string())
+        };
+        match (self.not_before, self.not_after) {
+            (None, None) => write!(f, "always valid"),
+            (Some(not_before), None) => write!(f, "valid from {}", format(not_before)),
+            (None, Some(not_after)) => write!(f, "valid until {}", format(not_after)),
+            (Some(not_before), Some(not_after)) => write!(
+                f,
+                "valid from {} until {}",
+                format(not_before),
+                format(not_after)
+            ),
+        }
+    }
+}
+
+/// Parses a point in time given either as seconds since the Unix epoch or
+/// in RFC 3339 format, e.g. `2026-01-01T00:00:00Z`.
+pub fn parse_timestamp(s: &str) -> Result<u64, Err> {
+    let s = s.trim();
+    if let Ok(timestamp) = s.parse::<u64>() {
+        return Ok(timestamp);
+    }
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .and_then(|time| u64::try_from(time.timestamp()).ok())
+        .ok_or_else(|| Err::InvalidTimestamp(s.to_string()))
+}
+
+/// The claims of a key rotation record, signed by the previous key: from
+/// `effective_at` onwards, tokens are signed by the next key instead.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct KeyRotationClaims {
+    /// RFC 7638 thumbprint of the previous key.
+    pub previous: String,
+    /// RFC 7638 thumbprint of the next key.
+    pub next: String,
+    pub effective_at: u64,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_key_validity() {
+        let validity =
+            KeyValidity::from_jwk(r#"{"kty": "RSA", "not_before": 100, "not_after": 200}"#)
+                .unwrap();
+        assert!(validity.contains(100));
+        assert!(!validity.contains(200));
+        assert!(validity.check(DateTime::from_timestamp(150, 0)).is_ok());
+        assert!(matches!(
+            validity.check(DateTime::from_timestamp(250, 0)),
+            Err(Err::KeyNotValidAt(_))
+        ));
+        assert!(matches!(validity.check(None), Err(Err::MissingIssuedAt)));
+
+        let unbounded = KeyValidity::from_jwk(r#"{"kty": "RSA"}"#).unwrap();
+        assert!(unbounded.check(None).is_ok());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1767225600").unwrap(), 1767225600);
+        assert_eq!(parse_timestamp("2026-01-01T00:00:00Z").unwrap(), 1767225600);
+        assert!(matches!(
+            parse_timestamp("next tuesday"),
+            Err(Err::InvalidTimestamp(_))
+        ));
+    }
+}
@@ -16,20 +16,95 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, Utc};
 use jwt_compact::{
     alg::{Rsa, RsaPrivateKey, RsaPublicKey},
     jwk::{JsonWebKey, JwkError},
     AlgorithmExt, Claims, CreationError, Header, ParseError, Token, UntrustedToken,
     ValidationError,
 };
-use serde::{Deserialize, Serialize};
+use rsa::traits::PublicKeyParts;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::str::FromStr;
 use thiserror::Error;
 
+pub mod audit;
+pub mod compact_key;
+pub mod delivery;
+pub mod key_validity;
+pub mod line_item;
+pub mod money;
+pub mod revocation;
+pub mod statement;
+pub mod thumbprint;
+
+pub use audit::{AuditCiphertext, AuditSeed};
+pub use compact_key::{check_key_set, CompactKey};
+pub use delivery::{DeliveryConfirmation, DeliveryReceipt, DELIVERY_RECEIPT_TYPE};
+pub use key_validity::{parse_timestamp, KeyRotationClaims, KeyValidity};
+pub use line_item::{total_quantity, LineItem, LineItemDisclosure};
+pub use money::Money;
+pub use revocation::{
+    check_revocation, token_hash, NonMembershipProof, RevocationListClaims, RevocationRoot,
+    RevocationTree,
+};
+pub use statement::{
+    Bucket, BucketDefinition, BucketDisclosure, DateWindow, Statements, UnitPriceBand,
+};
+pub use thumbprint::{jwk_thumbprint, jwk_thumbprint_uri, rsa_thumbprint, ThumbprintHash};
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PublicOutput {
     pub pks: Vec<String>,
     pub claims: CustomClaims,
+    /// Number of private claims left out of `claims`.
+    pub hidden_claims: usize,
+    /// Number of line item fields left out of `claims`, over all lines.
+    pub hidden_line_item_fields: usize,
+    pub buckets: Vec<BucketDisclosure>,
+    pub unit_price_bands: Vec<UnitPriceBand>,
+    pub date_windows: Vec<DateWindow>,
+    /// Root and version of each candidate key's revocation list, if any.
+    pub revocation: Vec<RevocationRoot>,
+    /// Public part of the carrier's delivery receipt, if one was given.
+    pub delivery: Option<DeliveryConfirmation>,
+    /// All of the claims, encrypted to the auditor's key, if one was given.
+    pub audit: Option<AuditCiphertext>,
+}
+
+impl PublicOutput {
+    /// The public output for a validated token: its public claims and the
+    /// outcome of each statement about its claims.
+    pub fn new(
+        pks: Vec<String>,
+        claims: &CustomClaims,
+        statements: &Statements,
+    ) -> Result<Self, Err> {
+        let buckets = statements
+            .buckets
+            .iter()
+            .map(|definition| definition.disclose(claims))
+            .collect::<Result<Vec<BucketDisclosure>, Err>>()?;
+        for band in statements.unit_price_bands.iter() {
+            band.check(claims)?;
+        }
+        for window in statements.date_windows.iter() {
+            window.check(claims)?;
+        }
+
+        Ok(PublicOutput {
+            pks,
+            claims: claims.get_public_claims(),
+            hidden_claims: claims.items().iter().filter(|item| item.is_private).count(),
+            hidden_line_item_fields: claims.hidden_line_item_fields(),
+            buckets,
+            unit_price_bands: statements.unit_price_bands.clone(),
+            date_windows: statements.date_windows.clone(),
+            revocation: Vec::new(),
+            delivery: None,
+            audit: None,
+        })
+    }
 }
 
 #[derive(Error, Debug)]
@@ -48,30 +123,189 @@ pub enum Err {
 
     #[error("Failed to parse key string: {0}")]
     KeyParseError(#[from] serde_json::Error),
+
+    #[error("JWK is missing required member '{0}'")]
+    MissingJwkMember(&'static str),
+
+    #[error("JWK member '{0}' must be a string")]
+    InvalidJwkMember(&'static str),
+
+    #[error("Invalid RSA public key: {0}")]
+    InvalidKey(String),
+
+    #[error("Candidate keys must be sorted by thumbprint with none repeated")]
+    KeySetNotCanonical,
+
+    #[error("Key may not be used to verify signatures: {0}")]
+    KeyNotForVerification(String),
+
+    #[error("Key is for the {0} algorithm, expected RS256")]
+    UnsupportedAlgorithm(String),
+
+    #[error("Token is not a proof of delivery")]
+    NotAProofOfDelivery,
+
+    #[error("Token names another key, '{0}'")]
+    KeyIdMismatch(String),
+
+    #[error("Token does not name its signing key")]
+    MissingKid,
+
+    #[error("Unsupported JWK key type: {0}")]
+    UnsupportedKeyType(String),
+
+    #[error("Unsupported thumbprint hash: {0}")]
+    UnsupportedHash(String),
+
+    #[error("Invalid line item '{0}', expected PRODUCT:QUANTITY:UNIT:UNIT_PRICE")]
+    InvalidLineItem(String),
+
+    #[error("Unknown line item field: {0}")]
+    InvalidLineItemField(String),
+
+    #[error("Cannot total quantities in {0} and {1}")]
+    MixedUnits(String, String),
+
+    #[error("Total quantity is too large")]
+    QuantityOverflow,
+
+    #[error("Invalid bucket definition '{0}', expected CLAIM:B1,B2,... with ascending boundaries")]
+    InvalidBucketDefinition(String),
+
+    #[error("Buckets for claim '{0}' are invalid: {1}")]
+    InvalidBuckets(String, String),
+
+    #[error("Token has no claim '{0}'")]
+    MissingClaim(String),
+
+    #[error("Claim '{0}' is not a non-negative integer")]
+    NonNumericClaim(String),
+
+    #[error("Claim '{0}' does not fall in any bucket")]
+    NoMatchingBucket(String),
+
+    #[error("Invalid amount of money '{0}', expected e.g. '4000.00 GBP'")]
+    InvalidMoney(String),
+
+    #[error("Unknown ISO 4217 currency: {0}")]
+    UnknownCurrency(String),
+
+    #[error("Currency {0} does not match {1}")]
+    CurrencyMismatch(String, String),
+
+    #[error("Amount of money is too large")]
+    MoneyOverflow,
+
+    #[error("Invalid unit price band '{0}', expected e.g. 'cost/quantity=3.50 GBP..4.50 GBP'")]
+    InvalidUnitPriceBand(String),
+
+    #[error("Unit price is outside the band {0}")]
+    UnitPriceOutOfBand(String),
+
+    #[error("Invalid hash '{0}', expected 64 hex digits")]
+    InvalidHash(String),
+
+    #[error("Token is not a revocation list")]
+    NotARevocationList,
+
+    #[error("Token has been revoked")]
+    TokenRevoked,
+
+    #[error("Got {0} revocation lists for {1} public keys, expected one per key")]
+    RevocationListCount(usize, usize),
+
+    #[error("Missing proof that the token is not revoked")]
+    MissingNonMembershipProof,
+
+    #[error("Token has no 'iat' claim, which is required by keys with a validity window")]
+    MissingIssuedAt,
+
+    #[error("Key was not valid when the token was issued at {0}")]
+    KeyNotValidAt(String),
+
+    #[error("Invalid time '{0}', expected seconds since the Unix epoch or RFC 3339")]
+    InvalidTimestamp(String),
+
+    #[error("Token is not a key rotation record")]
+    NotAKeyRotation,
+
+    #[error("Invalid date window '{0}', expected e.g. 'delivery_date=2025-04-01..2026-03-31' or a period such as '12m'")]
+    InvalidDateWindow(String),
+
+    #[error("Claim '{0}' is not a date in YYYY-MM-DD format")]
+    InvalidDateClaim(String),
+
+    #[error("Date is outside the window {0}")]
+    DateOutOfWindow(String),
+
+    #[error("Delivery receipt is not signed by any of the carrier keys")]
+    DeliveryReceiptNotSigned,
+
+    #[error("Delivery receipt does not have the same reference as the invoice")]
+    ReferenceMismatch,
+
+    #[error("Token is not a delivery receipt")]
+    NotADeliveryReceipt,
+
+    #[error("Delivery receipt has status '{0}', expected 'delivered'")]
+    DeliveryNotCompleted(String),
+
+    #[error("Carrier key {0} is also a candidate key of the invoice")]
+    CarrierKeyIsInvoiceKey(String),
+
+    #[error("Delivery receipt is the invoice itself")]
+    DeliveryReceiptIsInvoice,
+
+    #[error("Failed to encrypt claims to the auditor: {0}")]
+    AuditEncryptionFailed(String),
+
+    #[error("Failed to decrypt the audit ciphertext: {0}")]
+    AuditDecryptionFailed(String),
+
+    #[error("Audit ciphertext is encrypted to another key, {0}")]
+    AuditKeyMismatch(String),
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-struct ClaimItem {
-    key: String,
-    value: String,
-    is_private: bool,
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ClaimItem {
+    pub key: String,
+    pub value: String,
+    pub is_private: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CustomClaims {
     claims: Vec<ClaimItem>,
+    #[serde(default)]
+    line_items: Vec<LineItem>,
+    #[serde(default)]
+    line_item_disclosure: LineItemDisclosure,
 }
 
 impl FromIterator<ClaimItem> for CustomClaims {
     fn from_iter<I: IntoIterator<Item = ClaimItem>>(iter: I) -> Self {
         let claims: Vec<ClaimItem> = iter.into_iter().collect();
-        CustomClaims { claims }
+        CustomClaims {
+            claims,
+            line_items: Vec::new(),
+            line_item_disclosure: LineItemDisclosure::default(),
+        }
+    }
+}
+
+impl Default for CustomClaims {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl CustomClaims {
     pub fn new() -> Self {
-        CustomClaims { claims: Vec::new() }
+        CustomClaims {
+            claims: Vec::new(),
+            line_items: Vec::new(),
+            line_item_disclosure: LineItemDisclosure::default(),
+        }
     }
 
     pub fn add(&mut self, key: String, value: String, is_private: bool) {
@@ -82,30 +316,108 @@ impl CustomClaims {
         });
     }
 
-    pub fn get_public_claims(&self) -> Self {
+    pub fn get(&self, key: &str) -> Option<&str> {
         self.claims
+            .iter()
+            .find(|claim_item| claim_item.key == key)
+            .map(|claim_item| claim_item.value.as_str())
+    }
+
+    /// The claims other than the line items, in the order they are signed.
+    pub fn items(&self) -> &[ClaimItem] {
+        &self.claims
+    }
+
+    pub fn items_mut(&mut self) -> &mut Vec<ClaimItem> {
+        &mut self.claims
+    }
+
+    pub fn add_money(&mut self, key: String, value: &Money, is_private: bool) {
+        self.add(key, value.to_string(), is_private);
+    }
+
+    pub fn add_line_item(&mut self, line_item: LineItem) {
+        self.line_items.push(line_item);
+    }
+
+    pub fn line_items(&self) -> &[LineItem] {
+        &self.line_items
+    }
+
+    pub fn set_line_item_disclosure(&mut self, disclosure: LineItemDisclosure) {
+        self.line_item_disclosure = disclosure;
+    }
+
+    /// Number of line item fields that [`Self::get_public_claims`] redacts.
+    pub fn hidden_line_item_fields(&self) -> usize {
+        self.line_items
+            .iter()
+            .map(|line_item| line_item.hidden_fields(&self.line_item_disclosure))
+            .sum()
+    }
+
+    pub fn get_public_claims(&self) -> Self {
+        let mut public_claims: Self = self
+            .claims
             .clone()
             .into_iter()
             .filter(|claim_item| !claim_item.is_private)
-            .collect()
+            .collect();
+
+        let disclosure = self.line_item_disclosure;
+        public_claims.line_item_disclosure = disclosure;
+        public_claims.line_items = self
+            .line_items
+            .iter()
+            .map(|line_item| line_item.redact(&disclosure))
+            .collect();
+
+        // Totalled before redaction, so the total is disclosed even when the
+        // per-line quantities are not.  Lines that cannot be totalled, being
+        // in different units, have no total to disclose.
+        if disclosure.total_quantity {
+            if let Ok(total_quantity) = total_quantity(&self.line_items) {
+                public_claims.add(
+                    "total_quantity".to_string(),
+                    total_quantity.to_string(),
+                    false,
+                );
+            }
+        }
+
+        public_claims
     }
 
-    pub fn pretty_print(&self) -> String {
-        let mut result = "{".to_string();
-        let mut custom_claims = self.claims.iter();
-        if let Some(first_claim_item) = custom_claims.next() {
-            result += &format!(
-                "\n    \"{}\": \"{}\"",
-                first_claim_item.key, first_claim_item.value
-            )
-            .to_string();
-        }
-        for claim_item in custom_claims {
-            result +=
-                &format!(",\n    \"{}\": \"{}\"", claim_item.key, claim_item.value).to_string();
+    /// The claims as a JSON object mapping each key to its value.
+    pub fn to_json_object(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut object: serde_json::Map<String, serde_json::Value> = self
+            .claims
+            .iter()
+            .map(|claim_item| {
+                (
+                    claim_item.key.clone(),
+                    serde_json::Value::String(claim_item.value.clone()),
+                )
+            })
+            .collect();
+
+        if !self.line_items.is_empty() {
+            let line_items = self
+                .line_items
+                .iter()
+                .map(|line_item| serde_json::Value::Object(line_item.to_json_object()))
+                .collect();
+            object.insert(
+                "line_items".to_string(),
+                serde_json::Value::Array(line_items),
+            );
         }
-        result += "\n}";
-        result
+        object
+    }
+
+    pub fn pretty_print(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json_object())
+            .expect("a map of strings always serialises")
     }
 }
 
@@ -123,15 +435,71 @@ impl std::fmt::Display for CustomClaims {
     }
 }
 
+/// Media type set in the header of proof of delivery tokens, so that other
+/// tokens signed by the same key cannot be passed off as one.
+pub const POD_TYPE: &str = "pod+jwt";
+
 pub struct Issuer {
     alg: Rsa,
     s_k: RsaPrivateKey,
     pub p_k: RsaPublicKey,
+    /// Identifies the signing key in the header of each token: the JWK's
+    /// `kid` member, or else its RFC 7638 thumbprint.
+    pub kid: String,
 }
 
 impl Issuer {
     pub fn generate_token(&self, claims: &CustomClaims) -> Result<String, Err> {
-        let header = Header::empty();
+        self.generate_token_at(claims, Utc::now())
+    }
+
+    /// Generates a token whose `iat` claim is `issued_at`.
+    pub fn generate_token_at(
+        &self,
+        claims: &CustomClaims,
+        issued_at: DateTime<Utc>,
+    ) -> Result<String, Err> {
+        self.sign_claims_at(claims, issued_at, POD_TYPE)
+    }
+
+    /// Signs a carrier's delivery receipt, which has its own token type so
+    /// that it cannot stand in for an invoice or the other way round.
+    pub fn sign_delivery_receipt(&self, claims: &CustomClaims) -> Result<String, Err> {
+        self.sign_claims_at(claims, Utc::now(), delivery::DELIVERY_RECEIPT_TYPE)
+    }
+
+    fn sign_claims_at(
+        &self,
+        claims: &CustomClaims,
+        issued_at: DateTime<Utc>,
+        token_type: &str,
+    ) -> Result<String, Err> {
+        let header = Header::empty()
+            .with_key_id(self.kid.clone())
+            .with_token_type(token_type);
+        let mut claims = Claims::new(claims);
+        claims.issued_at = Some(issued_at);
+
+        self.alg
+            .token(&header, &claims, &self.s_k)
+            .map_err(Err::TokenGenerationError)
+    }
+
+    pub fn sign_revocation_list(&self, claims: &RevocationListClaims) -> Result<String, Err> {
+        let header = Header::empty()
+            .with_key_id(self.kid.clone())
+            .with_token_type(revocation::REVOCATION_LIST_TYPE);
+        let claims = Claims::new(claims);
+
+        self.alg
+            .token(&header, &claims, &self.s_k)
+            .map_err(Err::TokenGenerationError)
+    }
+
+    pub fn sign_key_rotation(&self, claims: &KeyRotationClaims) -> Result<String, Err> {
+        let header = Header::empty()
+            .with_key_id(self.kid.clone())
+            .with_token_type(key_validity::KEY_ROTATION_TYPE);
         let claims = Claims::new(claims);
 
         self.alg
@@ -147,37 +515,217 @@ impl FromStr for Issuer {
         let alg = Rsa::rs256();
         let s_k = RsaPrivateKey::try_from(&jwk)?;
         let p_k = RsaPublicKey::try_from(&jwk)?;
-        Ok(Self { alg, s_k, p_k })
+        let kid = match JwkMetadata::from_jwk(jwk_str)?.kid {
+            Some(kid) => kid,
+            None => rsa_thumbprint(&p_k.n().to_bytes_be(), &p_k.e().to_bytes_be()),
+        };
+        Ok(Self { alg, s_k, p_k, kid })
     }
 }
 
 pub struct Validator {
     alg: Rsa,
     pub p_k: RsaPublicKey,
+    pub validity: KeyValidity,
+    pub kid: Option<String>,
+    pub thumbprint: String,
 }
 
 impl Validator {
+    /// Checks the token's type and signature, that it does not name another
+    /// key and that it was issued within the key's validity window.
     pub fn validate_token_integrity(&self, token: &str) -> Result<Token<CustomClaims>, Err> {
+        self.validate_claims(token, POD_TYPE, Err::NotAProofOfDelivery)
+    }
+
+    /// Checks a carrier's delivery receipt as [`Self::validate_token_integrity`]
+    /// checks a proof of delivery.
+    pub fn validate_delivery_receipt(&self, token: &str) -> Result<Token<CustomClaims>, Err> {
+        self.validate_claims(
+            token,
+            delivery::DELIVERY_RECEIPT_TYPE,
+            Err::NotADeliveryReceipt,
+        )
+    }
+
+    fn validate_claims(
+        &self,
+        token: &str,
+        token_type: &str,
+        wrong_type: Err,
+    ) -> Result<Token<CustomClaims>, Err> {
         let token: UntrustedToken = UntrustedToken::new(token).map_err(Err::TokenParseError)?;
+        let header = token.header();
+        if header.token_type.as_deref() != Some(token_type) {
+            return Err(wrong_type);
+        }
+        match &header.key_id {
+            Some(kid) => self.check_key_id(kid)?,
+            None => return Err(Err::MissingKid),
+        }
 
-        self.alg
+        let token: Token<CustomClaims> = self
+            .alg
             .validator(&self.p_k)
             .validate(&token)
-            .map_err(Err::TokenValidationError)
+            .map_err(Err::TokenValidationError)?;
+        self.validity.check(token.claims().issued_at)?;
+        Ok(token)
+    }
+
+    pub fn validate_revocation_list(&self, list: &str) -> Result<RevocationListClaims, Err> {
+        self.validate_typed(
+            list,
+            revocation::REVOCATION_LIST_TYPE,
+            Err::NotARevocationList,
+        )
+    }
+
+    pub fn validate_key_rotation(&self, record: &str) -> Result<KeyRotationClaims, Err> {
+        self.validate_typed(
+            record,
+            key_validity::KEY_ROTATION_TYPE,
+            Err::NotAKeyRotation,
+        )
+    }
+
+    /// Validates a token other than a proof of delivery, which must carry
+    /// `token_type` in its header; `wrong_type` is returned otherwise.  Its
+    /// `kid` is only checked when present, as records signed before they
+    /// named their key carry none.
+    fn validate_typed<T: DeserializeOwned + Clone>(
+        &self,
+        token: &str,
+        token_type: &str,
+        wrong_type: Err,
+    ) -> Result<T, Err> {
+        let token: UntrustedToken = UntrustedToken::new(token).map_err(Err::TokenParseError)?;
+        let header = token.header();
+        if header.token_type.as_deref() != Some(token_type) {
+            return Err(wrong_type);
+        }
+        if let Some(kid) = &header.key_id {
+            self.check_key_id(kid)?;
+        }
+
+        let token: Token<T> = self
+            .alg
+            .validator(&self.p_k)
+            .validate(&token)
+            .map_err(Err::TokenValidationError)?;
+        Ok(token.claims().custom.clone())
+    }
+
+    /// Checks that a token's `kid` names this key, by its JWK `kid` or its
+    /// thumbprint.
+    fn check_key_id(&self, kid: &String) -> Result<(), Err> {
+        if Some(kid) != self.kid.as_ref() && *kid != self.thumbprint {
+            return Err(Err::KeyIdMismatch(kid.clone()));
+        }
+        Ok(())
     }
 }
 
+/// Decodes a token's header and claims without checking its signature.
+pub fn decode_untrusted_token(token: &str) -> Result<(Header, Claims<CustomClaims>), Err> {
+    let token: UntrustedToken = UntrustedToken::new(token)?;
+    let claims = token.deserialize_claims_unchecked::<CustomClaims>()?;
+    Ok((token.header().clone(), claims))
+}
+
+/// JWK members that restrict what a key may be used for, and its `kid`.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct JwkMetadata {
+    #[serde(rename = "use")]
+    pub public_key_use: Option<String>,
+    pub key_ops: Option<Vec<String>>,
+    pub alg: Option<String>,
+    pub kid: Option<String>,
+}
+
+impl JwkMetadata {
+    pub fn from_jwk(jwk_str: &str) -> Result<Self, Err> {
+        Ok(serde_json::from_str::<JwkMetadata>(jwk_str)?)
+    }
+
+    /// Checks that the key may be used to verify RS256 signatures, as far as
+    /// its `use`, `key_ops` and `alg` members say.
+    pub fn check(&self) -> Result<(), Err> {
+        if let Some(public_key_use) = &self.public_key_use {
+            if public_key_use != "sig" {
+                return Err(Err::KeyNotForVerification(format!(
+                    "its use is '{public_key_use}'"
+                )));
+            }
+        }
+        if let Some(key_ops) = &self.key_ops {
+            if !key_ops.iter().any(|op| op == "verify") {
+                return Err(Err::KeyNotForVerification(format!(
+                    "its key_ops are {key_ops:?}"
+                )));
+            }
+        }
+        if let Some(alg) = &self.alg {
+            if alg != "RS256" {
+                return Err(Err::UnsupportedAlgorithm(alg.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == JwkMetadata::default()
+    }
+}
+
+/// Lists the members that are present, e.g. `kid abc, use sig`.
+impl std::fmt::Display for JwkMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let members = [
+            self.kid.as_ref().map(|kid| format!("kid {kid}")),
+            self.public_key_use
+                .as_ref()
+                .map(|public_key_use| format!("use {public_key_use}")),
+            self.key_ops
+                .as_ref()
+                .map(|key_ops| format!("key_ops {}", key_ops.join(" "))),
+            self.alg.as_ref().map(|alg| format!("alg {alg}")),
+        ];
+        let members: Vec<String> = members.into_iter().flatten().collect();
+        write!(f, "{}", members.join(", "))
+    }
+}
+
+/// Checks that a JWK may be used to verify RS256 signatures, as far as its
+/// `use`, `key_ops` and `alg` members say, and returns its `kid`.
+pub fn check_key_metadata(jwk_str: &str) -> Result<Option<String>, Err> {
+    let metadata = JwkMetadata::from_jwk(jwk_str)?;
+    metadata.check()?;
+    Ok(metadata.kid)
+}
+
 impl FromStr for Validator {
     type Err = Err;
     fn from_str(jwk_str: &str) -> Result<Self, Self::Err> {
+        let kid = check_key_metadata(jwk_str)?;
         let jwk = serde_json::from_str::<JsonWebKey>(jwk_str)?;
         let alg = Rsa::rs256();
         let p_k = RsaPublicKey::try_from(&jwk)?;
-        Ok(Self { alg, p_k })
+        let validity = KeyValidity::from_jwk(jwk_str)?;
+        let thumbprint = rsa_thumbprint(&p_k.n().to_bytes_be(), &p_k.e().to_bytes_be());
+        Ok(Self {
+            alg,
+            p_k,
+            validity,
+            kid,
+            thumbprint,
+        })
     }
 }
 
 #[cfg(test)]
+// `it_works` compares against an owned string, as it always has.
+#[allow(clippy::cmp_owned)]
 mod tests {
 
     use super::*;
@@ -272,4 +820,131 @@ mod tests {
             "Token validation should fail, but it passed."
         );
     }
+
+    #[test]
+    fn test_pretty_print_escapes_values() {
+        let mut claims = CustomClaims::new();
+        claims.add("product".to_string(), "\"raw\" beans".to_string(), false);
+
+        assert_eq!(
+            claims.pretty_print(),
+            "{\n  \"product\": \"\\\"raw\\\" beans\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_token_type_and_key_metadata() {
+        let iss = SECRET_KEY.parse::<Issuer>().unwrap();
+        let validator = PUBLIC_KEY.parse::<Validator>().unwrap();
+        let claims = CustomClaims::new();
+
+        let token = iss.generate_token(&claims).unwrap();
+        let (header, _) = decode_untrusted_token(&token).unwrap();
+        assert_eq!(header.token_type.as_deref(), Some(POD_TYPE));
+        assert_eq!(
+            header.key_id.as_deref(),
+            Some("6ab0e8e4bc121fc287e35d3e5e0efb8a")
+        );
+
+        // Any other token signed by the same key, such as a login token.
+        let login = iss
+            .alg
+            .token(&Header::empty(), &Claims::new(&claims), &iss.s_k)
+            .unwrap();
+        assert!(matches!(
+            validator.validate_token_integrity(&login),
+            Err(Err::NotAProofOfDelivery)
+        ));
+
+        let header = Header::empty()
+            .with_key_id("another key")
+            .with_token_type(POD_TYPE);
+        let misnamed = iss
+            .alg
+            .token(&header, &Claims::new(&claims), &iss.s_k)
+            .unwrap();
+        assert!(matches!(
+            validator.validate_token_integrity(&misnamed),
+            Err(Err::KeyIdMismatch(_))
+        ));
+
+        let unnamed = iss
+            .alg
+            .token(
+                &Header::empty().with_token_type(POD_TYPE),
+                &Claims::new(&claims),
+                &iss.s_k,
+            )
+            .unwrap();
+        assert!(matches!(
+            validator.validate_token_integrity(&unnamed),
+            Err(Err::MissingKid)
+        ));
+
+        // Key rotation records name their key as well.
+        let rotation = KeyRotationClaims {
+            previous: validator.thumbprint.clone(),
+            next: validator.thumbprint.clone(),
+            effective_at: 0,
+        };
+        let record = iss.sign_key_rotation(&rotation).unwrap();
+        let record_kid = UntrustedToken::new(&record)
+            .unwrap()
+            .header()
+            .key_id
+            .clone();
+        assert_eq!(record_kid, Some(iss.kid.clone()));
+        assert_eq!(validator.validate_key_rotation(&record).unwrap(), rotation);
+
+        let for_encryption = PUBLIC_KEY.replace(r#""use": "sig""#, r#""use": "enc""#);
+        assert!(matches!(
+            for_encryption.parse::<Validator>(),
+            Err(Err::KeyNotForVerification(_))
+        ));
+        let sign_only = PUBLIC_KEY.replace(r#""verify""#, r#""sign""#);
+        assert!(matches!(
+            sign_only.parse::<Validator>(),
+            Err(Err::KeyNotForVerification(_))
+        ));
+        let other_alg = PUBLIC_KEY.replace("RS256", "PS256");
+        assert!(matches!(
+            other_alg.parse::<Validator>(),
+            Err(Err::UnsupportedAlgorithm(_))
+        ));
+    }
+
+    #[test]
+    fn test_line_items_disclosure() {
+        let mut claims = CustomClaims::new();
+        claims.add_line_item("raw coffee beans:1000:kg:4.00 GBP".parse().unwrap());
+        claims.add_line_item("decaf beans:250:kg:5.20 GBP".parse().unwrap());
+        claims.set_line_item_disclosure("product,total_quantity".parse().unwrap());
+
+        let iss = SECRET_KEY.parse::<Issuer>().unwrap();
+        let token = iss.generate_token(&claims).unwrap();
+        let validator = PUBLIC_KEY.parse::<Validator>().unwrap();
+        let valid_token = validator.validate_token_integrity(&token).unwrap();
+
+        let public_claims = valid_token.claims().custom.get_public_claims();
+        assert_eq!(
+            serde_json::Value::Object(public_claims.to_json_object()),
+            serde_json::json!({
+                "total_quantity": "1250",
+                "line_items": [
+                    {"product": "raw coffee beans"},
+                    {"product": "decaf beans"}
+                ]
+            })
+        );
+
+        // Three fields of each line are redacted.
+        let output = PublicOutput::new(
+            vec![PUBLIC_KEY.to_string()],
+            &valid_token.claims().custom,
+            &Statements::default(),
+        )
+        .unwrap();
+        assert_eq!(output.hidden_claims, 0);
+        assert_eq!(output.hidden_line_item_fields, 6);
+    }
 }
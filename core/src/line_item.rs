@@ -0,0 +1,219 @@
+//! Structured invoice lines, and which of their fields may be disclosed.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::{Err, Money};
+
+/// One line of an invoice.  Every field is optional so that the same type can
+/// describe a line in the public output with undisclosed fields removed.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct LineItem {
+    pub product: Option<String>,
+    pub quantity: Option<u64>,
+    pub unit: Option<String>,
+    pub unit_price: Option<Money>,
+}
+
+impl LineItem {
+    pub fn new(product: String, quantity: u64, unit: String, unit_price: Money) -> Self {
+        LineItem {
+            product: Some(product),
+            quantity: Some(quantity),
+            unit: Some(unit),
+            unit_price: Some(unit_price),
+        }
+    }
+
+    /// The quantity multiplied by the unit price.
+    pub fn total(&self) -> Option<Result<Money, Err>> {
+        Some(self.unit_price.as_ref()?.checked_mul(self.quantity?))
+    }
+
+    pub(crate) fn redact(&self, disclosure: &LineItemDisclosure) -> Self {
+        LineItem {
+            product: self.product.clone().filter(|_| disclosure.product),
+            quantity: self.quantity.filter(|_| disclosure.quantity),
+            unit: self.unit.clone().filter(|_| disclosure.unit),
+            unit_price: self.unit_price.clone().filter(|_| disclosure.unit_price),
+        }
+    }
+
+    /// Number of the line's fields that `disclosure` leaves out.
+    pub(crate) fn hidden_fields(&self, disclosure: &LineItemDisclosure) -> usize {
+        [
+            self.product.is_some() && !disclosure.product,
+            self.quantity.is_some() && !disclosure.quantity,
+            self.unit.is_some() && !disclosure.unit,
+            self.unit_price.is_some() && !disclosure.unit_price,
+        ]
+        .into_iter()
+        .filter(|&hidden| hidden)
+        .count()
+    }
+
+    pub fn to_json_object(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut object = serde_json::Map::new();
+        if let Some(product) = &self.product {
+            object.insert("product".to_string(), product.clone().into());
+        }
+        if let Some(quantity) = self.quantity {
+            object.insert("quantity".to_string(), quantity.into());
+        }
+        if let Some(unit) = &self.unit {
+            object.insert("unit".to_string(), unit.clone().into());
+        }
+        if let Some(unit_price) = &self.unit_price {
+            object.insert("unit_price".to_string(), unit_price.to_string().into());
+        }
+        object
+    }
+}
+
+/// The sum of the quantities of `line_items`, which must all be in the same
+/// unit, since e.g. kilograms and bags cannot be added up.
+pub fn total_quantity(line_items: &[LineItem]) -> Result<u64, Err> {
+    if let Some(first) = line_items.first() {
+        if let Some(other) = line_items.iter().find(|line| line.unit != first.unit) {
+            let name = |line: &LineItem| line.unit.clone().unwrap_or_else(|| "no unit".to_string());
+            return Err(Err::MixedUnits(name(first), name(other)));
+        }
+    }
+    line_items
+        .iter()
+        .filter_map(|line_item| line_item.quantity)
+        .try_fold(0u64, |total, quantity| {
+            total.checked_add(quantity).ok_or(Err::QuantityOverflow)
+        })
+}
+
+/// Parses `PRODUCT:QUANTITY:UNIT:UNIT_PRICE`, e.g.
+/// `raw coffee beans:1000:kg:4.00 GBP`.  The product name may itself contain
+/// colons.
+impl FromStr for LineItem {
+    type Err = Err;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Err::InvalidLineItem(s.to_string());
+        let mut fields = s.rsplitn(4, ':');
+        let unit_price = fields.next().ok_or_else(invalid)?.trim();
+        let unit = fields.next().ok_or_else(invalid)?.trim();
+        let quantity = fields.next().ok_or_else(invalid)?.trim();
+        let product = fields.next().ok_or_else(invalid)?.trim();
+
+        if product.is_empty() || unit.is_empty() {
+            return Err(invalid());
+        }
+        let quantity = quantity.parse::<u64>().map_err(|_| invalid())?;
+
+        Ok(LineItem::new(
+            product.to_string(),
+            quantity,
+            unit.to_string(),
+            unit_price.parse()?,
+        ))
+    }
+}
+
+/// Which line item fields the guest discloses.  It is part of the signed
+/// claims, so the issuer decides what a prover may reveal.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineItemDisclosure {
+    pub product: bool,
+    pub quantity: bool,
+    pub unit: bool,
+    pub unit_price: bool,
+    /// Disclose the sum of the line quantities as the `total_quantity` claim.
+    pub total_quantity: bool,
+}
+
+/// Parses a comma separated list of field names, e.g. `product,total_quantity`.
+impl FromStr for LineItemDisclosure {
+    type Err = Err;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut disclosure = LineItemDisclosure::default();
+        for field in s.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            match field {
+                "product" => disclosure.product = true,
+                "quantity" => disclosure.quantity = true,
+                "unit" => disclosure.unit = true,
+                "unit_price" => disclosure.unit_price = true,
+                "total_quantity" => disclosure.total_quantity = true,
+                _ => return Err(Err::InvalidLineItemField(field.to_string())),
+            }
+        }
+        Ok(disclosure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_line_item() {
+        assert_eq!(
+            "raw coffee beans: 1000:kg:4.00 GBP"
+                .parse::<LineItem>()
+                .unwrap(),
+            LineItem::new(
+                "raw coffee beans".to_string(),
+                1000,
+                "kg".to_string(),
+                "4.00 GBP".parse().unwrap()
+            )
+        );
+        assert_eq!(
+            "blend: house:5:kg:6.50 GBP"
+                .parse::<LineItem>()
+                .unwrap()
+                .product
+                .as_deref(),
+            Some("blend: house")
+        );
+        assert!("raw coffee beans:1000:kg".parse::<LineItem>().is_err());
+        assert!("raw coffee beans:-3:kg:4.00 GBP"
+            .parse::<LineItem>()
+            .is_err());
+        assert!("raw coffee beans:1000:kg:4.00".parse::<LineItem>().is_err());
+    }
+
+    #[test]
+    fn test_total_quantity() {
+        let line = |s: &str| s.parse::<LineItem>().unwrap();
+        assert_eq!(
+            total_quantity(&[
+                line("beans:1000:kg:4.00 GBP"),
+                line("decaf:250:kg:5.20 GBP")
+            ])
+            .unwrap(),
+            1250
+        );
+        assert!(matches!(
+            total_quantity(&[
+                line("beans:1000:kg:4.00 GBP"),
+                line("sacks:20:bag:1.00 GBP")
+            ]),
+            Err(Err::MixedUnits(_, _))
+        ));
+        let huge = format!("beans:{}:kg:0.01 GBP", u64::MAX);
+        assert!(matches!(
+            total_quantity(&[line(&huge), line("beans:1:kg:0.01 GBP")]),
+            Err(Err::QuantityOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_parse_disclosure() {
+        let disclosure = "product, total_quantity"
+            .parse::<LineItemDisclosure>()
+            .unwrap();
+        assert!(disclosure.product && disclosure.total_quantity);
+        assert!(!disclosure.quantity && !disclosure.unit && !disclosure.unit_price);
+
+        assert!(matches!(
+            "price".parse::<LineItemDisclosure>(),
+            Err(Err::InvalidLineItemField(_))
+        ));
+    }
+}
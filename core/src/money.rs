@@ -0,0 +1,223 @@
+//! Exact decimal amounts of money in an ISO 4217 currency.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+use crate::Err;
+
+/// Number of digits after the decimal point (the "minor unit") of each active
+/// ISO 4217 currency.
+fn minor_unit_exponent(currency: &str) -> Option<u32> {
+    match currency {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => Some(0),
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => Some(3),
+        "CLF" | "UYW" => Some(4),
+        "AED" | "AFN" | "ALL" | "AMD" | "ANG" | "AOA" | "ARS" | "AUD" | "AWG" | "AZN" | "BAM"
+        | "BBD" | "BDT" | "BGN" | "BMD" | "BND" | "BOB" | "BRL" | "BSD" | "BTN" | "BWP" | "BYN"
+        | "BZD" | "CAD" | "CDF" | "CHF" | "CNY" | "COP" | "CRC" | "CUP" | "CVE" | "CZK" | "DKK"
+        | "DOP" | "DZD" | "EGP" | "ERN" | "ETB" | "EUR" | "FJD" | "FKP" | "GBP" | "GEL" | "GHS"
+        | "GIP" | "GMD" | "GTQ" | "GYD" | "HKD" | "HNL" | "HTG" | "HUF" | "IDR" | "ILS" | "INR"
+        | "IRR" | "JMD" | "KES" | "KGS" | "KHR" | "KPW" | "KYD" | "KZT" | "LAK" | "LBP" | "LKR"
+        | "LRD" | "LSL" | "MAD" | "MDL" | "MGA" | "MKD" | "MMK" | "MNT" | "MOP" | "MRU" | "MUR"
+        | "MVR" | "MWK" | "MXN" | "MYR" | "MZN" | "NAD" | "NGN" | "NIO" | "NOK" | "NPR" | "NZD"
+        | "PAB" | "PEN" | "PGK" | "PHP" | "PKR" | "PLN" | "QAR" | "RON" | "RSD" | "RUB" | "SAR"
+        | "SBD" | "SCR" | "SDG" | "SEK" | "SGD" | "SHP" | "SLE" | "SOS" | "SRD" | "SSP" | "STN"
+        | "SVC" | "SYP" | "SZL" | "THB" | "TJS" | "TMT" | "TOP" | "TRY" | "TTD" | "TWD" | "TZS"
+        | "UAH" | "USD" | "UYU" | "UZS" | "VES" | "WST" | "XCD" | "YER" | "ZAR" | "ZMW" | "ZWG" => {
+            Some(2)
+        }
+        _ => None,
+    }
+}
+
+/// A non-negative amount held as an integer number of minor units (e.g.
+/// pence), so that no rounding ever takes place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Money {
+    minor_units: u64,
+    currency: String,
+}
+
+impl Money {
+    pub fn from_minor_units(minor_units: u64, currency: &str) -> Result<Self, Err> {
+        minor_unit_exponent(currency).ok_or_else(|| Err::UnknownCurrency(currency.to_string()))?;
+        Ok(Money {
+            minor_units,
+            currency: currency.to_string(),
+        })
+    }
+
+    pub fn minor_units(&self) -> u64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    fn exponent(&self) -> u32 {
+        minor_unit_exponent(&self.currency).expect("currency checked on construction")
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, Err> {
+        self.check_currency(other)?;
+        let minor_units = self
+            .minor_units
+            .checked_add(other.minor_units)
+            .ok_or(Err::MoneyOverflow)?;
+        Money::from_minor_units(minor_units, &self.currency)
+    }
+
+    pub fn checked_mul(&self, factor: u64) -> Result<Money, Err> {
+        let minor_units = self
+            .minor_units
+            .checked_mul(factor)
+            .ok_or(Err::MoneyOverflow)?;
+        Money::from_minor_units(minor_units, &self.currency)
+    }
+
+    pub fn check_currency(&self, other: &Money) -> Result<(), Err> {
+        if self.currency != other.currency {
+            return Err(Err::CurrencyMismatch(
+                self.currency.clone(),
+                other.currency.clone(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let exponent = self.exponent();
+        if exponent == 0 {
+            return write!(f, "{} {}", self.minor_units, self.currency);
+        }
+        let scale = 10u64.pow(exponent);
+        write!(
+            f,
+            "{}.{:0width$} {}",
+            self.minor_units / scale,
+            self.minor_units % scale,
+            self.currency,
+            width = exponent as usize
+        )
+    }
+}
+
+/// Parses an amount followed by its currency code, e.g. `4000.00 GBP`.  The
+/// amount may not have more decimal places than the currency allows.
+impl FromStr for Money {
+    type Err = Err;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Err::InvalidMoney(s.to_string());
+        let (amount, currency) = s.trim().rsplit_once(' ').ok_or_else(invalid)?;
+        let currency = currency.trim();
+        let exponent = minor_unit_exponent(currency)
+            .ok_or_else(|| Err::UnknownCurrency(currency.to_string()))?;
+
+        let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty()
+            || !all_digits(whole)
+            || !all_digits(fraction)
+            || fraction.len() > exponent as usize
+            || (amount.contains('.') && fraction.is_empty())
+        {
+            return Err(invalid());
+        }
+
+        let scale = 10u64.pow(exponent);
+        let whole = whole.parse::<u64>().map_err(|_| invalid())?;
+        let fraction = format!("{:0<width$}", fraction, width = exponent as usize);
+        let fraction = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u64>().map_err(|_| invalid())?
+        };
+        let minor_units = whole
+            .checked_mul(scale)
+            .and_then(|units| units.checked_add(fraction))
+            .ok_or(Err::MoneyOverflow)?;
+
+        Money::from_minor_units(minor_units, currency)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let cost = "4000.00 GBP".parse::<Money>().unwrap();
+        assert_eq!(cost.minor_units(), 400000);
+        assert_eq!(cost.to_string(), "4000.00 GBP");
+
+        assert_eq!("4.5 GBP".parse::<Money>().unwrap().to_string(), "4.50 GBP");
+        assert_eq!("1500 JPY".parse::<Money>().unwrap().minor_units(), 1500);
+        assert_eq!(
+            "1.005 KWD".parse::<Money>().unwrap().to_string(),
+            "1.005 KWD"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_inexact_amounts() {
+        assert!(matches!(
+            "4000.001 GBP".parse::<Money>(),
+            Err(Err::InvalidMoney(_))
+        ));
+        assert!(matches!(
+            "1.5 JPY".parse::<Money>(),
+            Err(Err::InvalidMoney(_))
+        ));
+        assert!(matches!(
+            "-4 GBP".parse::<Money>(),
+            Err(Err::InvalidMoney(_))
+        ));
+        assert!(matches!(
+            "4000.00".parse::<Money>(),
+            Err(Err::InvalidMoney(_))
+        ));
+        assert!(matches!(
+            "4000.00 XYZ".parse::<Money>(),
+            Err(Err::UnknownCurrency(_))
+        ));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let price = "4.20 GBP".parse::<Money>().unwrap();
+        let total = price
+            .checked_mul(1000)
+            .unwrap()
+            .checked_add(&"0.01 GBP".parse().unwrap())
+            .unwrap();
+        assert_eq!(total.to_string(), "4200.01 GBP");
+
+        assert!(matches!(
+            price.checked_add(&"1.00 EUR".parse().unwrap()),
+            Err(Err::CurrencyMismatch(_, _))
+        ));
+        assert!(matches!(
+            price.checked_mul(u64::MAX),
+            Err(Err::MoneyOverflow)
+        ));
+    }
+}
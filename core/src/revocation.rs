@@ -0,0 +1,293 @@
+//! Revocation lists published by issuers as sorted Merkle trees of token
+//! hashes, with proofs that a token is not on a list.
+//!
+//! The leaves are the revoked hashes in ascending order, between a minimum
+//! and a maximum sentinel.  A token is shown not to be revoked by proving the
+//! inclusion of two adjacent leaves, one below and one above its hash.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Err, Validator};
+
+pub type Hash = [u8; 32];
+
+/// Media type set in the header of signed revocation lists, so that they
+/// cannot be mistaken for proofs of delivery.
+pub const REVOCATION_LIST_TYPE: &str = "revocation-list+jwt";
+
+const MIN_SENTINEL: Hash = [0x00; 32];
+const MAX_SENTINEL: Hash = [0xff; 32];
+
+pub fn token_hash(token: &str) -> Hash {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+pub fn hash_to_hex(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn hash_from_hex(s: &str) -> Result<Hash, Err> {
+    let invalid = || Err::InvalidHash(s.to_string());
+    if s.len() != 64 || !s.is_ascii() {
+        return Err(invalid());
+    }
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(hash)
+}
+
+fn leaf_hash(value: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The claims of a signed revocation list.  Only the root is signed; the
+/// revoked hashes are published alongside it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct RevocationListClaims {
+    /// RFC 7638 thumbprint of the issuer's key.
+    pub issuer: String,
+    pub version: u64,
+    /// Hex encoded Merkle root.
+    pub root: String,
+    pub leaf_count: u64,
+}
+
+/// Root and version of a candidate key's revocation list, as committed by the
+/// guest.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct RevocationRoot {
+    pub root: String,
+    pub version: u64,
+}
+
+/// Path from a leaf to the root.  A node without a sibling, the last one on
+/// a level with an odd number of nodes, is promoted to the next level as is.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MerklePath {
+    pub index: u64,
+    pub siblings: Vec<Hash>,
+}
+
+impl MerklePath {
+    fn root(&self, leaf: &Hash, leaf_count: u64) -> Option<Hash> {
+        if self.index >= leaf_count {
+            return None;
+        }
+        let mut hash = leaf_hash(leaf);
+        let mut index = self.index;
+        let mut width = leaf_count;
+        let mut siblings = self.siblings.iter();
+        while width > 1 {
+            if index % 2 == 1 {
+                hash = node_hash(siblings.next()?, &hash);
+            } else if index + 1 < width {
+                hash = node_hash(&hash, siblings.next()?);
+            }
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none().then_some(hash)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct NonMembershipProof {
+    pub low: Hash,
+    pub low_path: MerklePath,
+    pub high: Hash,
+    pub high_path: MerklePath,
+}
+
+impl NonMembershipProof {
+    pub fn verify(&self, value: &Hash, root: &Hash, leaf_count: u64) -> bool {
+        self.low < *value
+            && *value < self.high
+            && self.high_path.index == self.low_path.index + 1
+            && self.low_path.root(&self.low, leaf_count).as_ref() == Some(root)
+            && self.high_path.root(&self.high, leaf_count).as_ref() == Some(root)
+    }
+}
+
+pub struct RevocationTree {
+    /// Every level of the tree, from the leaf hashes up to the root.
+    levels: Vec<Vec<Hash>>,
+    leaves: Vec<Hash>,
+}
+
+impl RevocationTree {
+    pub fn new(revoked: &[Hash]) -> Self {
+        let mut leaves = vec![MIN_SENTINEL, MAX_SENTINEL];
+        leaves.extend(revoked.iter().copied());
+        leaves.sort();
+        leaves.dedup();
+
+        let mut levels = vec![leaves.iter().map(leaf_hash).collect::<Vec<Hash>>()];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [node] => *node,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(level);
+        }
+
+        RevocationTree { levels, leaves }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn leaf_count(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    fn path(&self, index: usize) -> MerklePath {
+        let mut siblings = Vec::new();
+        let mut i = index;
+        for level in self.levels.iter().take(self.levels.len() - 1) {
+            if let Some(sibling) = level.get(i ^ 1) {
+                siblings.push(*sibling);
+            }
+            i /= 2;
+        }
+        MerklePath {
+            index: index as u64,
+            siblings,
+        }
+    }
+
+    pub fn prove_non_membership(&self, value: &Hash) -> Result<NonMembershipProof, Err> {
+        let high = match self.leaves.binary_search(value) {
+            Ok(_) => return Err(Err::TokenRevoked),
+            Err(high) => high,
+        };
+        // The sentinels guarantee that 0 < high < leaves.len(), except for the
+        // sentinel values themselves.
+        if high == 0 || high == self.leaves.len() {
+            return Err(Err::TokenRevoked);
+        }
+        Ok(NonMembershipProof {
+            low: self.leaves[high - 1],
+            low_path: self.path(high - 1),
+            high: self.leaves[high],
+            high_path: self.path(high),
+        })
+    }
+
+    pub fn claims(&self, issuer: String, version: u64) -> RevocationListClaims {
+        RevocationListClaims {
+            issuer,
+            version,
+            root: hash_to_hex(&self.root()),
+            leaf_count: self.leaf_count(),
+        }
+    }
+}
+
+/// Checks the signed revocation list of every candidate key, one per key in
+/// the same order, and that the token is not on the list of the key that
+/// signed it.  Returns the root and version of each list.
+pub fn check_revocation(
+    token: &str,
+    pks: &[String],
+    signer: usize,
+    lists: &[String],
+    proof: Option<&NonMembershipProof>,
+) -> Result<Vec<RevocationRoot>, Err> {
+    if lists.is_empty() {
+        return Ok(Vec::new());
+    }
+    if lists.len() != pks.len() {
+        return Err(Err::RevocationListCount(lists.len(), pks.len()));
+    }
+
+    let mut roots = Vec::new();
+    for (index, (pk, list)) in pks.iter().zip(lists.iter()).enumerate() {
+        let claims = pk.parse::<Validator>()?.validate_revocation_list(list)?;
+        let root = hash_from_hex(&claims.root)?;
+        if index == signer {
+            let proof = proof.ok_or(Err::MissingNonMembershipProof)?;
+            if !proof.verify(&token_hash(token), &root, claims.leaf_count) {
+                return Err(Err::TokenRevoked);
+            }
+        }
+        roots.push(RevocationRoot {
+            root: claims.root,
+            version: claims.version,
+        });
+    }
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn hashes(n: u8) -> Vec<Hash> {
+        (0..n).map(|i| token_hash(&format!("token {i}"))).collect()
+    }
+
+    #[test]
+    fn test_non_membership() {
+        // Odd and even leaf counts exercise promoted nodes.
+        for n in [0, 1, 2, 5, 8] {
+            let revoked = hashes(n);
+            let tree = RevocationTree::new(&revoked);
+            let value = token_hash("not revoked");
+
+            let proof = tree.prove_non_membership(&value).unwrap();
+            assert!(proof.verify(&value, &tree.root(), tree.leaf_count()));
+            assert!(!proof.verify(&value, &[7; 32], tree.leaf_count()));
+
+            for hash in revoked.iter() {
+                assert!(!proof.verify(hash, &tree.root(), tree.leaf_count()));
+                assert!(matches!(
+                    tree.prove_non_membership(hash),
+                    Err(Err::TokenRevoked)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_membership_rejects_non_adjacent_leaves() {
+        let tree = RevocationTree::new(&hashes(6));
+        let value = token_hash("not revoked");
+        let mut proof = tree.prove_non_membership(&value).unwrap();
+
+        // Skipping over a revoked hash between the two leaves must fail.
+        proof.low = MIN_SENTINEL;
+        proof.low_path = tree.path(0);
+        proof.high = MAX_SENTINEL;
+        proof.high_path = tree.path(tree.leaves.len() - 1);
+        assert!(!proof.verify(&value, &tree.root(), tree.leaf_count()));
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let hash = token_hash("token");
+        assert_eq!(hash_from_hex(&hash_to_hex(&hash)).unwrap(), hash);
+        assert!(matches!(hash_from_hex("abc"), Err(Err::InvalidHash(_))));
+    }
+}
@@ -0,0 +1,493 @@
+//! Statements about private claims that the guest proves without revealing
+//! the claims themselves.  They are public input: the guest commits each
+//! statement along with its outcome.
+
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::{CustomClaims, Err, Money};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct Statements {
+    #[serde(default)]
+    pub buckets: Vec<BucketDefinition>,
+    #[serde(default)]
+    pub unit_price_bands: Vec<UnitPriceBand>,
+    #[serde(default)]
+    pub date_windows: Vec<DateWindow>,
+    /// Signed revocation list of each candidate key, in the same order as
+    /// the keys, or none to skip the revocation check.
+    #[serde(default)]
+    pub revocation_lists: Vec<String>,
+    /// Public JWK of an auditor to encrypt all of the claims to.
+    #[serde(default)]
+    pub auditor_key: Option<String>,
+}
+
+/// A range of values with both bounds inclusive; no upper bound means the
+/// range is open-ended.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Bucket {
+    pub label: String,
+    pub min: u64,
+    pub max: Option<u64>,
+}
+
+impl Bucket {
+    pub fn contains(&self, value: u64) -> bool {
+        value >= self.min && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl std::fmt::Display for Bucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) => write!(f, "{} to {}", self.min, max),
+            None => write!(f, "{} or more", self.min),
+        }
+    }
+}
+
+/// Maps the value of a numeric claim onto one of a set of buckets, which
+/// must be in ascending order without overlapping, so that at most one
+/// bucket contains the value.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct BucketDefinition {
+    pub claim: String,
+    pub buckets: Vec<Bucket>,
+}
+
+impl BucketDefinition {
+    /// Buckets split at ascending boundaries, from zero up to the first
+    /// boundary and from the last boundary upwards.
+    pub fn from_boundaries(claim: String, boundaries: &[u64]) -> Result<Self, Err> {
+        if boundaries.is_empty() || boundaries.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(Err::InvalidBucketDefinition(claim));
+        }
+
+        let mut buckets = Vec::new();
+        let mut min = 0;
+        for &boundary in boundaries.iter().filter(|&&boundary| boundary > 0) {
+            buckets.push(Bucket {
+                label: format!("{}-{}", min, boundary - 1),
+                min,
+                max: Some(boundary - 1),
+            });
+            min = boundary;
+        }
+        buckets.push(Bucket {
+            label: format!("{}+", min),
+            min,
+            max: None,
+        });
+
+        Ok(BucketDefinition { claim, buckets })
+    }
+
+    /// Checks that the labels are distinct and that the ranges are well
+    /// formed, ascending and disjoint.  Definitions may come from a file or
+    /// a request, not only from [`Self::from_boundaries`].
+    pub fn check(&self) -> Result<(), Err> {
+        let invalid = |reason: &str| Err::InvalidBuckets(self.claim.clone(), reason.to_string());
+        if self.buckets.is_empty() {
+            return Err(invalid("there are no buckets"));
+        }
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            if bucket.max.is_some_and(|max| max < bucket.min) {
+                return Err(invalid(&format!(
+                    "bucket '{}' ends before it starts",
+                    bucket.label
+                )));
+            }
+            if self.buckets[..index]
+                .iter()
+                .any(|other| other.label == bucket.label)
+            {
+                return Err(invalid(&format!("label '{}' is repeated", bucket.label)));
+            }
+        }
+        for pair in self.buckets.windows(2) {
+            if pair[0].max.is_none_or(|max| max >= pair[1].min) {
+                return Err(invalid(&format!(
+                    "bucket '{}' is not below bucket '{}'",
+                    pair[0].label, pair[1].label
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn disclose(&self, claims: &CustomClaims) -> Result<BucketDisclosure, Err> {
+        self.check()?;
+        let value = claims
+            .get(&self.claim)
+            .ok_or_else(|| Err::MissingClaim(self.claim.clone()))?;
+        let value = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| Err::NonNumericClaim(self.claim.clone()))?;
+
+        let bucket = self
+            .buckets
+            .iter()
+            .find(|bucket| bucket.contains(value))
+            .ok_or_else(|| Err::NoMatchingBucket(self.claim.clone()))?;
+
+        Ok(BucketDisclosure {
+            definition: self.clone(),
+            bucket: bucket.clone(),
+        })
+    }
+}
+
+/// Parses `CLAIM:B1,B2,...`, see [`BucketDefinition::from_boundaries`].
+impl FromStr for BucketDefinition {
+    type Err = Err;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Err::InvalidBucketDefinition(s.to_string());
+        let (claim, boundaries) = s.rsplit_once(':').ok_or_else(invalid)?;
+        let boundaries = boundaries
+            .split(',')
+            .map(|boundary| boundary.trim().parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<u64>, Err>>()?;
+        Self::from_boundaries(claim.trim().to_string(), &boundaries)
+    }
+}
+
+/// The bucket a claim falls in, committed in full rather than by its label.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct BucketDisclosure {
+    pub definition: BucketDefinition,
+    pub bucket: Bucket,
+}
+
+/// States that the unit price, the money claim `cost_claim` divided by the
+/// integer claim `quantity_claim`, lies between `min` and `max` inclusive.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct UnitPriceBand {
+    pub cost_claim: String,
+    pub quantity_claim: String,
+    pub min: Money,
+    pub max: Money,
+}
+
+impl UnitPriceBand {
+    pub fn check(&self, claims: &CustomClaims) -> Result<(), Err> {
+        self.min.check_currency(&self.max)?;
+
+        let cost = claims
+            .get(&self.cost_claim)
+            .ok_or_else(|| Err::MissingClaim(self.cost_claim.clone()))?
+            .parse::<Money>()?;
+        cost.check_currency(&self.min)?;
+        let quantity = claims
+            .get(&self.quantity_claim)
+            .ok_or_else(|| Err::MissingClaim(self.quantity_claim.clone()))?
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| Err::NonNumericClaim(self.quantity_claim.clone()))?;
+
+        // min <= cost / quantity <= max, multiplied out to avoid division.
+        let cost = cost.minor_units() as u128;
+        let quantity = quantity as u128;
+        if quantity == 0
+            || cost < self.min.minor_units() as u128 * quantity
+            || cost > self.max.minor_units() as u128 * quantity
+        {
+            return Err(Err::UnitPriceOutOfBand(self.to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for UnitPriceBand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}={}..{}",
+            self.cost_claim, self.quantity_claim, self.min, self.max
+        )
+    }
+}
+
+/// Parses `COST/QUANTITY=MIN..MAX`, e.g. `cost/quantity=3.50 GBP..4.50 GBP`.
+impl FromStr for UnitPriceBand {
+    type Err = Err;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Err::InvalidUnitPriceBand(s.to_string());
+        let (claims, band) = s.split_once('=').ok_or_else(invalid)?;
+        let (cost_claim, quantity_claim) = claims.split_once('/').ok_or_else(invalid)?;
+        let (min, max) = band.split_once("..").ok_or_else(invalid)?;
+
+        let band = UnitPriceBand {
+            cost_claim: cost_claim.trim().to_string(),
+            quantity_claim: quantity_claim.trim().to_string(),
+            min: min.parse()?,
+            max: max.parse()?,
+        };
+        band.min.check_currency(&band.max)?;
+        if band.min.minor_units() > band.max.minor_units() {
+            return Err(invalid());
+        }
+        Ok(band)
+    }
+}
+
+/// Claim holding the date the goods were delivered, as `YYYY-MM-DD`.
+pub const DELIVERY_DATE_CLAIM: &str = "delivery_date";
+
+/// States that the date claim `claim` lies between `from` and `until`
+/// inclusive.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct DateWindow {
+    pub claim: String,
+    pub from: NaiveDate,
+    pub until: NaiveDate,
+}
+
+impl DateWindow {
+    /// The window of length `period` ending on `today`, where the period is a
+    /// number of days, months or years, e.g. `30d`, `12m` or `1y`.
+    pub fn trailing(claim: &str, period: &str, today: NaiveDate) -> Result<Self, Err> {
+        let invalid = || Err::InvalidDateWindow(period.to_string());
+        let period = period.trim();
+        // Split before the last character, which need not be ASCII.
+        let (count, unit) = period
+            .char_indices()
+            .next_back()
+            .map(|(index, _)| period.split_at(index))
+            .ok_or_else(invalid)?;
+        let count = count.parse::<u32>().map_err(|_| invalid())?;
+        let from = match unit {
+            "d" => today.checked_sub_days(Days::new(count.into())),
+            "m" => today.checked_sub_months(Months::new(count)),
+            "y" => count
+                .checked_mul(12)
+                .and_then(|months| today.checked_sub_months(Months::new(months))),
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+
+        Ok(DateWindow {
+            claim: claim.to_string(),
+            from,
+            until: today,
+        })
+    }
+
+    pub fn check(&self, claims: &CustomClaims) -> Result<(), Err> {
+        let value = claims
+            .get(&self.claim)
+            .ok_or_else(|| Err::MissingClaim(self.claim.clone()))?;
+        let date = NaiveDate::from_str(value.trim())
+            .map_err(|_| Err::InvalidDateClaim(self.claim.clone()))?;
+        if date < self.from || date > self.until {
+            return Err(Err::DateOutOfWindow(self.to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for DateWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}..{}", self.claim, self.from, self.until)
+    }
+}
+
+/// Parses `CLAIM=FROM..UNTIL`, e.g. `delivery_date=2025-04-01..2026-03-31`.
+impl FromStr for DateWindow {
+    type Err = Err;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Err::InvalidDateWindow(s.to_string());
+        let (claim, window) = s.split_once('=').ok_or_else(invalid)?;
+        let (from, until) = window.split_once("..").ok_or_else(invalid)?;
+
+        let window = DateWindow {
+            claim: claim.trim().to_string(),
+            from: NaiveDate::from_str(from.trim()).map_err(|_| invalid())?,
+            until: NaiveDate::from_str(until.trim()).map_err(|_| invalid())?,
+        };
+        if window.claim.is_empty() || window.from > window.until {
+            return Err(invalid());
+        }
+        Ok(window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_bucket_from_boundaries() {
+        let definition = "quantity:1000,5000".parse::<BucketDefinition>().unwrap();
+        assert_eq!(definition.claim, "quantity");
+        assert_eq!(
+            definition
+                .buckets
+                .iter()
+                .map(|bucket| bucket.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["0-999", "1000-4999", "5000+"]
+        );
+
+        assert!("quantity:5000,1000".parse::<BucketDefinition>().is_err());
+        assert!("quantity".parse::<BucketDefinition>().is_err());
+    }
+
+    #[test]
+    fn test_bucket_disclosure() {
+        let mut claims = CustomClaims::new();
+        claims.add("quantity".to_string(), "1000".to_string(), true);
+        claims.add("product".to_string(), "raw coffee beans".to_string(), false);
+
+        let disclosure = "quantity:1000,5000"
+            .parse::<BucketDefinition>()
+            .unwrap()
+            .disclose(&claims)
+            .unwrap();
+        assert_eq!(disclosure.bucket.label, "1000-4999");
+        assert_eq!(disclosure.bucket.to_string(), "1000 to 4999");
+
+        assert!(matches!(
+            "product:10"
+                .parse::<BucketDefinition>()
+                .unwrap()
+                .disclose(&claims),
+            Err(Err::NonNumericClaim(_))
+        ));
+        let gap = BucketDefinition {
+            claim: "quantity".to_string(),
+            buckets: vec![Bucket {
+                label: "small".to_string(),
+                min: 0,
+                max: Some(999),
+            }],
+        };
+        assert!(matches!(
+            gap.disclose(&claims),
+            Err(Err::NoMatchingBucket(_))
+        ));
+    }
+
+    #[test]
+    fn test_bucket_definition_check() {
+        let bucket = |label: &str, min: u64, max: Option<u64>| Bucket {
+            label: label.to_string(),
+            min,
+            max,
+        };
+        let definition = |buckets: Vec<Bucket>| BucketDefinition {
+            claim: "quantity".to_string(),
+            buckets,
+        };
+        let mut claims = CustomClaims::new();
+        claims.add("quantity".to_string(), "1000".to_string(), true);
+
+        let valid = definition(vec![
+            bucket("small", 0, Some(999)),
+            bucket("large", 1000, None),
+        ]);
+        assert_eq!(valid.disclose(&claims).unwrap().bucket.label, "large");
+
+        for invalid in [
+            definition(Vec::new()),
+            definition(vec![
+                bucket("small", 0, Some(999)),
+                bucket("small", 1000, None),
+            ]),
+            definition(vec![bucket("any", 0, None), bucket("large", 1000, None)]),
+            definition(vec![
+                bucket("small", 0, Some(1000)),
+                bucket("large", 1000, None),
+            ]),
+            definition(vec![
+                bucket("large", 1000, None),
+                bucket("small", 0, Some(999)),
+            ]),
+            definition(vec![bucket("backwards", 2000, Some(1000))]),
+        ] {
+            assert!(matches!(
+                invalid.disclose(&claims),
+                Err(Err::InvalidBuckets(_, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_unit_price_band() {
+        let mut claims = CustomClaims::new();
+        claims.add("quantity".to_string(), "1000".to_string(), true);
+        claims.add("cost".to_string(), "4000.00 GBP".to_string(), true);
+
+        let band = "cost/quantity=3.50 GBP..4.00 GBP"
+            .parse::<UnitPriceBand>()
+            .unwrap();
+        assert!(band.check(&claims).is_ok());
+
+        let band = "cost/quantity=4.01 GBP..4.50 GBP"
+            .parse::<UnitPriceBand>()
+            .unwrap();
+        assert!(matches!(
+            band.check(&claims),
+            Err(Err::UnitPriceOutOfBand(_))
+        ));
+
+        let band = "cost/quantity=3.50 EUR..4.50 EUR"
+            .parse::<UnitPriceBand>()
+            .unwrap();
+        assert!(matches!(
+            band.check(&claims),
+            Err(Err::CurrencyMismatch(_, _))
+        ));
+
+        assert!("cost/quantity=4.50 GBP..3.50 GBP"
+            .parse::<UnitPriceBand>()
+            .is_err());
+        assert!("cost/quantity=3.50 GBP..4.50 EUR"
+            .parse::<UnitPriceBand>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_date_window() {
+        let mut claims = CustomClaims::new();
+        claims.add(
+            DELIVERY_DATE_CLAIM.to_string(),
+            "2026-03-14".to_string(),
+            true,
+        );
+
+        let window = "delivery_date=2025-04-01..2026-03-31"
+            .parse::<DateWindow>()
+            .unwrap();
+        assert!(window.check(&claims).is_ok());
+
+        let today = NaiveDate::from_ymd_opt(2027, 3, 14).unwrap();
+        let past_year = DateWindow::trailing(DELIVERY_DATE_CLAIM, "12m", today).unwrap();
+        assert_eq!(
+            past_year.from,
+            NaiveDate::from_ymd_opt(2026, 3, 14).unwrap()
+        );
+        assert!(past_year.check(&claims).is_ok());
+
+        let past_month = DateWindow::trailing(DELIVERY_DATE_CLAIM, "30d", today).unwrap();
+        assert!(matches!(
+            past_month.check(&claims),
+            Err(Err::DateOutOfWindow(_))
+        ));
+
+        assert!(DateWindow::trailing(DELIVERY_DATE_CLAIM, "12w", today).is_err());
+        for period in ["12£", "£", "", "1２m"] {
+            assert!(matches!(
+                DateWindow::trailing(DELIVERY_DATE_CLAIM, period, today),
+                Err(Err::InvalidDateWindow(_))
+            ));
+        }
+        assert!("delivery_date=2026-03-31..2025-04-01"
+            .parse::<DateWindow>()
+            .is_err());
+    }
+}
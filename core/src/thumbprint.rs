@@ -0,0 +1,202 @@
+//! JSON Web Key thumbprints as specified in RFC 7638, with the URI form from
+//! RFC 9278.
+
+use base64::prelude::*;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::Err;
+
+const THUMBPRINT_URN_PREFIX: &str = "urn:ietf:params:oauth:jwk-thumbprint";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThumbprintHash {
+    #[default]
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl ThumbprintHash {
+    /// Hash algorithm name as registered in the IANA "Named Information Hash
+    /// Algorithm" registry, which RFC 9278 uses in thumbprint URIs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThumbprintHash::Sha256 => "sha-256",
+            ThumbprintHash::Sha384 => "sha-384",
+            ThumbprintHash::Sha512 => "sha-512",
+        }
+    }
+
+    fn digest(&self, input: &[u8]) -> Vec<u8> {
+        match self {
+            ThumbprintHash::Sha256 => Sha256::digest(input).to_vec(),
+            ThumbprintHash::Sha384 => Sha384::digest(input).to_vec(),
+            ThumbprintHash::Sha512 => Sha512::digest(input).to_vec(),
+        }
+    }
+}
+
+impl std::str::FromStr for ThumbprintHash {
+    type Err = Err;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sha-256" | "sha256" => Ok(ThumbprintHash::Sha256),
+            "sha-384" | "sha384" => Ok(ThumbprintHash::Sha384),
+            "sha-512" | "sha512" => Ok(ThumbprintHash::Sha512),
+            _ => Err(Err::UnsupportedHash(s.to_string())),
+        }
+    }
+}
+
+/// Required members for each supported key type, in lexicographic order.
+fn required_members(kty: &str) -> Result<&'static [&'static str], Err> {
+    match kty {
+        "RSA" => Ok(&["e", "kty", "n"]),
+        "EC" => Ok(&["crv", "kty", "x", "y"]),
+        "OKP" => Ok(&["crv", "kty", "x"]),
+        _ => Err(Err::UnsupportedKeyType(kty.to_string())),
+    }
+}
+
+/// Builds the canonical JSON object hashed to produce the thumbprint: the
+/// required members only, sorted, with no whitespace.
+pub fn thumbprint_input(jwk: &Map<String, Value>) -> Result<String, Err> {
+    let kty = jwk
+        .get("kty")
+        .ok_or(Err::MissingJwkMember("kty"))?
+        .as_str()
+        .ok_or(Err::InvalidJwkMember("kty"))?;
+
+    let mut members = Vec::new();
+    for &member in required_members(kty)? {
+        let value = jwk
+            .get(member)
+            .ok_or(Err::MissingJwkMember(member))?
+            .as_str()
+            .ok_or(Err::InvalidJwkMember(member))?;
+        members.push(format!("\"{}\":{}", member, serde_json::to_string(value)?));
+    }
+
+    Ok(format!("{{{}}}", members.join(",")))
+}
+
+pub fn jwk_thumbprint(jwk: &str, hash: ThumbprintHash) -> Result<String, Err> {
+    let jwk = serde_json::from_str::<Map<String, Value>>(jwk)?;
+    let input = thumbprint_input(&jwk)?;
+    Ok(BASE64_URL_SAFE_NO_PAD.encode(hash.digest(input.as_bytes())))
+}
+
+/// SHA-256 thumbprint of an RSA public key given as the big-endian bytes of
+/// its modulus and exponent, equal to that of its JWK.
+pub fn rsa_thumbprint(n: &[u8], e: &[u8]) -> String {
+    let input = format!(
+        r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+        BASE64_URL_SAFE_NO_PAD.encode(e),
+        BASE64_URL_SAFE_NO_PAD.encode(n)
+    );
+    BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(input.as_bytes()))
+}
+
+pub fn jwk_thumbprint_uri(jwk: &str, hash: ThumbprintHash) -> Result<String, Err> {
+    let thumbprint = jwk_thumbprint(jwk, hash)?;
+    Ok(format!(
+        "{}:{}:{}",
+        THUMBPRINT_URN_PREFIX,
+        hash.name(),
+        thumbprint
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // RFC 7638, section 3.1
+    const RFC7638_RSA_KEY: &str = r#"
+    {
+      "kty": "RSA",
+      "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+      "e": "AQAB",
+      "alg": "RS256",
+      "kid": "2011-04-29"
+    }
+    "#;
+
+    // RFC 8037, appendix A.3
+    const RFC8037_OKP_KEY: &str = r#"
+    {
+      "kty": "OKP",
+      "crv": "Ed25519",
+      "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"
+    }
+    "#;
+
+    // RFC 7517, appendix A.1
+    const RFC7517_EC_KEY: &str = r#"
+    {
+      "kty": "EC",
+      "crv": "P-256",
+      "x": "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+      "y": "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+      "use": "enc",
+      "kid": "1"
+    }
+    "#;
+
+    #[test]
+    fn test_rfc7638_rsa_thumbprint() {
+        assert_eq!(
+            jwk_thumbprint(RFC7638_RSA_KEY, ThumbprintHash::Sha256).unwrap(),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+        assert_eq!(
+            jwk_thumbprint(RFC7638_RSA_KEY, ThumbprintHash::Sha384).unwrap(),
+            "R9_OfJjSjaw8Fuum86UzK5ixTdN9bo9BaqPSiseq89DWfmqCdpSgUHus-cxDUNc8"
+        );
+        assert_eq!(
+            jwk_thumbprint(RFC7638_RSA_KEY, ThumbprintHash::Sha512).unwrap(),
+            "DpvEwocfn3FjeWWQjcJHzWrpKTIymKwgoL1xVgQcud48-qZDSRCr1zfWZQdHAJn_ciqXqPTSARyg-L-NyNGpVA"
+        );
+    }
+
+    #[test]
+    fn test_okp_and_ec_thumbprints() {
+        assert_eq!(
+            jwk_thumbprint(RFC8037_OKP_KEY, ThumbprintHash::Sha256).unwrap(),
+            "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k"
+        );
+        assert_eq!(
+            jwk_thumbprint(RFC7517_EC_KEY, ThumbprintHash::Sha256).unwrap(),
+            "cn-I_WNMClehiVp51i_0VpOENW1upEerA8sEam5hn-s"
+        );
+    }
+
+    #[test]
+    fn test_thumbprint_uri() {
+        assert_eq!(
+            jwk_thumbprint_uri(RFC7638_RSA_KEY, ThumbprintHash::Sha256).unwrap(),
+            "urn:ietf:params:oauth:jwk-thumbprint:sha-256:NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+    }
+
+    #[test]
+    fn test_thumbprint_rejects_bad_keys() {
+        assert!(matches!(
+            jwk_thumbprint(r#"{"kty": "RSA", "e": "AQAB"}"#, ThumbprintHash::Sha256),
+            Err(Err::MissingJwkMember("n"))
+        ));
+        assert!(matches!(
+            jwk_thumbprint(r#"{"kty": "oct", "k": "AAAA"}"#, ThumbprintHash::Sha256),
+            Err(Err::UnsupportedKeyType(_))
+        ));
+        assert!(matches!(
+            jwk_thumbprint(
+                r#"{"kty": "RSA", "e": 3, "n": "AQAB"}"#,
+                ThumbprintHash::Sha256
+            ),
+            Err(Err::InvalidJwkMember("e"))
+        ));
+    }
+}
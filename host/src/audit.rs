@@ -0,0 +1,72 @@
+//! Auditor keys, and recovery of the claims a receipt encrypted to them.
+
+use crate::{verify_receipt, Error};
+use jwt_compact::{
+    alg::{ModulusBits, Rsa},
+    jwk::JsonWebKey,
+};
+use jwt_core::{AuditSeed, CustomClaims};
+use rand_core::{OsRng, RngCore};
+use risc0_zkvm::Receipt;
+
+/// Fresh randomness for the guest to encrypt the claims with.
+pub fn random_seed() -> AuditSeed {
+    let mut seed = AuditSeed::default();
+    OsRng.fill_bytes(&mut seed);
+    seed
+}
+
+/// Generates a 3072-bit RSA auditor key pair, as secret and public JWKs.
+pub fn generate_auditor_key() -> Result<(String, String), Error> {
+    let (secret_key, public_key) = Rsa::generate(&mut OsRng, ModulusBits::ThreeKibibytes)
+        .map_err(|e| Error::BadInput(format!("Could not generate auditor key: {e}")))?;
+    let secret_jwk = serde_json::to_string_pretty(&JsonWebKey::from(secret_key.as_ref()))?;
+    let public_jwk = serde_json::to_string_pretty(&JsonWebKey::from(public_key.as_ref()))?;
+    Ok((secret_jwk, public_jwk))
+}
+
+/// Verifies the receipt and decrypts the claims it encrypted to the auditor.
+pub fn decrypt_receipt(receipt: &Receipt, secret_key: &str) -> Result<CustomClaims, Error> {
+    let output = verify_receipt(receipt)?;
+    let audit = output.audit.ok_or_else(|| {
+        Error::BadInput("Receipt has no claims encrypted to an auditor".to_string())
+    })?;
+    Ok(audit.decrypt(secret_key)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::report::test::fake_receipt;
+    use crate::report::VerificationReport;
+    use jwt_core::{AuditCiphertext, PublicOutput, Statements};
+
+    const AUDITOR_SK: &str = include_str!("../../test_data/coffee_company_sk.jwk");
+    const AUDITOR_PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+
+    #[test]
+    fn test_decrypt_receipt() {
+        let mut claims = CustomClaims::new();
+        claims.add("quantity".to_string(), "1000".to_string(), false);
+        claims.add("cost".to_string(), "4000.00 GBP".to_string(), true);
+        let mut output = PublicOutput::new(
+            vec![AUDITOR_PK.to_string()],
+            &claims,
+            &Statements::default(),
+        )
+        .unwrap();
+        let receipt = fake_receipt(&output);
+        assert!(matches!(
+            decrypt_receipt(&receipt, AUDITOR_SK),
+            Err(Error::BadInput(_))
+        ));
+
+        output.audit = Some(AuditCiphertext::encrypt(&claims, AUDITOR_PK, random_seed()).unwrap());
+        let receipt = fake_receipt(&output);
+        let decrypted = decrypt_receipt(&receipt, AUDITOR_SK).unwrap();
+        assert_eq!(decrypted.get("cost"), Some("4000.00 GBP"));
+        assert!(VerificationReport::from_receipt(&receipt)
+            .to_text()
+            .contains("encrypted to the auditor key"));
+    }
+}
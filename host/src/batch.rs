@@ -0,0 +1,430 @@
+//! Proving many proof of delivery tokens against one shared key set.
+//!
+//! Progress is recorded in a manifest after every token, so an interrupted
+//! batch can be resumed by running it again with the same manifest: tokens
+//! that already have a receipt are skipped, unless the key set or the proof
+//! options have changed.
+
+use crate::key_set::canonical_key_set;
+use crate::{prove_token_validation_with, serialize_receipt, Error, ProofOptions};
+use jwt_core::revocation::hash_to_hex;
+use jwt_core::token_hash;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    #[default]
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BatchEntry {
+    pub token: PathBuf,
+    #[serde(default)]
+    pub receipt: Option<PathBuf>,
+    #[serde(default)]
+    pub status: BatchStatus,
+    #[serde(default)]
+    pub started_at: Option<u64>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl BatchEntry {
+    pub fn new(token: PathBuf) -> Self {
+        BatchEntry {
+            token,
+            receipt: None,
+            status: BatchStatus::Pending,
+            started_at: None,
+            duration_ms: None,
+            error: None,
+        }
+    }
+
+    /// Whether the entry still needs proving. Succeeded entries are redone
+    /// if their receipt has since gone missing.
+    fn is_outstanding(&self) -> bool {
+        match (&self.status, &self.receipt) {
+            (BatchStatus::Succeeded, Some(receipt)) => !receipt.exists(),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchManifest {
+    #[serde(default)]
+    pub public_keys: Vec<PathBuf>,
+    /// Hash of the key set, as committed by the guest, and of the proof
+    /// options the receipts were proved with.  Receipts proved with other
+    /// keys, statements, revocation lists or auditor key are proved again.
+    #[serde(default)]
+    pub proof_inputs: Option<String>,
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchManifest {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| Error::io(format!("Could not read {}", path.display()), e))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Writes the manifest via a temporary file, so that an interruption
+    /// never leaves a truncated manifest behind.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)
+            .and_then(|_| std::fs::rename(&tmp_path, path))
+            .map_err(|e| Error::io(format!("Could not write {}", path.display()), e))
+    }
+
+    /// Builds a manifest from every `.jwt` file in a directory.
+    pub fn from_token_dir(dir: &Path) -> Result<Self, Error> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| Error::io(format!("Could not read {}", dir.display()), e))?;
+        let mut tokens = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|e| Error::io(format!("Could not read {}", dir.display()), e))?
+                .path();
+            if path.extension().is_some_and(|ext| ext == "jwt") {
+                tokens.push(path);
+            }
+        }
+        tokens.sort();
+
+        Ok(BatchManifest {
+            public_keys: Vec::new(),
+            proof_inputs: None,
+            entries: tokens.into_iter().map(BatchEntry::new).collect(),
+        })
+    }
+
+    /// Adds tokens from `other` that are not already in this manifest, keeping
+    /// the recorded outcome of the ones that are.
+    pub fn merge(&mut self, other: BatchManifest) {
+        for entry in other.entries {
+            if !self.entries.iter().any(|e| e.token == entry.token) {
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    pub fn count(&self, status: BatchStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+}
+
+pub struct BatchConfig {
+    pub out_dir: PathBuf,
+    pub manifest_path: PathBuf,
+    pub concurrency: usize,
+}
+
+fn file_stem(token: &Path) -> String {
+    token
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "receipt".to_string())
+}
+
+/// Where the receipt of `token` is written: named after the token file, with
+/// a hash of the token's path added if another token in the batch has the
+/// same file name, so that neither receipt overwrites the other.
+fn receipt_path(out_dir: &Path, token: &Path, entries: &[BatchEntry]) -> PathBuf {
+    let stem = file_stem(token);
+    let shared = entries
+        .iter()
+        .any(|entry| entry.token != token && file_stem(&entry.token) == stem);
+    if shared {
+        let hash = hash_to_hex(&token_hash(&token.to_string_lossy()));
+        out_dir.join(format!("{stem}-{}.bin", &hash[..8]))
+    } else {
+        out_dir.join(format!("{stem}.bin"))
+    }
+}
+
+/// Hash of the keys the guest commits for `pks`, which ignores their order,
+/// formatting and repeats but not their validity windows, and of `options`.
+fn proof_inputs_hash(pks: &[String], options: &ProofOptions) -> Result<String, Error> {
+    let mut inputs = canonical_key_set(pks)?
+        .iter()
+        .map(|(_, key)| key.to_jwk())
+        .collect::<Vec<String>>();
+    inputs.push(serde_json::to_string(options)?);
+    Ok(hash_to_hex(&token_hash(&inputs.join("\n"))))
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Proves every outstanding entry of the manifest with the zkVM prover.
+pub fn run_batch(
+    manifest: BatchManifest,
+    pks: &[String],
+    options: &ProofOptions,
+    config: &BatchConfig,
+) -> Result<BatchManifest, Error> {
+    run_batch_with(manifest, pks, options, config, |token, pks, options| {
+        serialize_receipt(&prove_token_validation_with(token, pks, options)?)
+    })
+}
+
+/// Proves every outstanding entry of the manifest with `prove`, which returns
+/// the serialised receipt, saving the manifest after each token.
+pub fn run_batch_with<F>(
+    mut manifest: BatchManifest,
+    pks: &[String],
+    options: &ProofOptions,
+    config: &BatchConfig,
+    prove: F,
+) -> Result<BatchManifest, Error>
+where
+    F: Fn(&str, &[String], &ProofOptions) -> Result<Vec<u8>, Error> + Sync,
+{
+    std::fs::create_dir_all(&config.out_dir)
+        .map_err(|e| Error::io(format!("Could not create {}", config.out_dir.display()), e))?;
+
+    let proof_inputs = proof_inputs_hash(pks, options)?;
+    if manifest.proof_inputs.as_ref() != Some(&proof_inputs) {
+        for entry in manifest.entries.iter_mut() {
+            if entry.status == BatchStatus::Succeeded {
+                entry.status = BatchStatus::Pending;
+            }
+        }
+        manifest.proof_inputs = Some(proof_inputs);
+    }
+
+    let outstanding: Vec<usize> = manifest
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.is_outstanding())
+        .map(|(index, _)| index)
+        .collect();
+    for &index in outstanding.iter() {
+        let receipt = receipt_path(
+            &config.out_dir,
+            &manifest.entries[index].token,
+            &manifest.entries,
+        );
+        let entry = &mut manifest.entries[index];
+        entry.status = BatchStatus::Pending;
+        entry.receipt = Some(receipt);
+    }
+    manifest.save(&config.manifest_path)?;
+
+    let next = AtomicUsize::new(0);
+    let manifest = Mutex::new(manifest);
+    let save_error: Mutex<Option<Error>> = Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for _ in 0..config.concurrency.max(1) {
+            scope.spawn(|| {
+                while let Some(&index) = outstanding.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    let (token_path, receipt_path) = {
+                        let manifest = manifest.lock().unwrap();
+                        let entry = &manifest.entries[index];
+                        (entry.token.clone(), entry.receipt.clone().unwrap())
+                    };
+
+                    let started_at = unix_time();
+                    let start = Instant::now();
+                    let result = std::fs::read_to_string(&token_path)
+                        .map_err(|e| {
+                            Error::io(format!("Could not read {}", token_path.display()), e)
+                        })
+                        .and_then(|token| prove(token.trim(), pks, options))
+                        .and_then(|receipt| {
+                            std::fs::write(&receipt_path, receipt).map_err(|e| {
+                                Error::io(format!("Could not write {}", receipt_path.display()), e)
+                            })
+                        });
+
+                    let mut manifest = manifest.lock().unwrap();
+                    let entry = &mut manifest.entries[index];
+                    entry.started_at = Some(started_at);
+                    entry.duration_ms = Some(start.elapsed().as_millis() as u64);
+                    match result {
+                        Ok(()) => {
+                            entry.status = BatchStatus::Succeeded;
+                            entry.error = None;
+                        }
+                        Err(e) => {
+                            entry.status = BatchStatus::Failed;
+                            entry.error = Some(e.to_string());
+                        }
+                    }
+                    if let Err(e) = manifest.save(&config.manifest_path) {
+                        save_error.lock().unwrap().get_or_insert(e);
+                    }
+                }
+            });
+        }
+    });
+
+    match save_error.into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => Ok(manifest.into_inner().unwrap()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jwt_core::{BucketDefinition, Statements};
+    use uuid::Uuid;
+
+    const PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+    const OTHER_PK: &str = include_str!("../../test_data/other_pk_2.jwk");
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zkpod-batch-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_batch_proves_and_resumes() {
+        let dir = temp_dir();
+        let tokens_dir = dir.join("tokens");
+        std::fs::create_dir_all(&tokens_dir).unwrap();
+        std::fs::write(tokens_dir.join("a.jwt"), "good").unwrap();
+        std::fs::write(tokens_dir.join("b.jwt"), "bad").unwrap();
+        std::fs::write(tokens_dir.join("notes.txt"), "ignored").unwrap();
+
+        let config = BatchConfig {
+            out_dir: dir.join("receipts"),
+            manifest_path: dir.join(MANIFEST_FILE_NAME),
+            concurrency: 2,
+        };
+        let pks = vec![PK.to_string()];
+        let options = ProofOptions::default();
+        let prove = |token: &str, _: &[String], _: &ProofOptions| match token {
+            "bad" => Err(Error::ProvingFailed("no key matches".to_string())),
+            token => Ok(token.as_bytes().to_vec()),
+        };
+
+        let manifest = BatchManifest::from_token_dir(&tokens_dir).unwrap();
+        assert_eq!(manifest.entries.len(), 2);
+
+        let manifest = run_batch_with(manifest, &pks, &options, &config, prove).unwrap();
+        assert_eq!(manifest.count(BatchStatus::Succeeded), 1);
+        assert_eq!(manifest.count(BatchStatus::Failed), 1);
+        assert_eq!(
+            std::fs::read(config.out_dir.join("a.bin")).unwrap(),
+            b"good".to_vec()
+        );
+        assert_eq!(
+            BatchManifest::load(&config.manifest_path).unwrap(),
+            manifest
+        );
+
+        // Only the failed token is proved again on resume.
+        std::fs::write(tokens_dir.join("b.jwt"), "fixed").unwrap();
+        let proved = AtomicUsize::new(0);
+        let manifest = run_batch_with(manifest, &pks, &options, &config, |token, pks, options| {
+            proved.fetch_add(1, Ordering::SeqCst);
+            prove(token, pks, options)
+        })
+        .unwrap();
+        assert_eq!(proved.load(Ordering::SeqCst), 1);
+        assert_eq!(manifest.count(BatchStatus::Succeeded), 2);
+
+        // Every token is proved again against a new key set.
+        let proved = AtomicUsize::new(0);
+        let pks = vec![PK.to_string(), OTHER_PK.to_string()];
+        let manifest = run_batch_with(manifest, &pks, &options, &config, |token, pks, options| {
+            proved.fetch_add(1, Ordering::SeqCst);
+            prove(token, pks, options)
+        })
+        .unwrap();
+        assert_eq!(proved.load(Ordering::SeqCst), 2);
+        assert_eq!(manifest.count(BatchStatus::Succeeded), 2);
+
+        // And again with a different bucket.
+        let proved = AtomicUsize::new(0);
+        let bucketed = ProofOptions {
+            statements: Statements {
+                buckets: vec!["quantity:1000".parse::<BucketDefinition>().unwrap()],
+                ..Statements::default()
+            },
+            ..ProofOptions::default()
+        };
+        let prove_counted = |token: &str, pks: &[String], options: &ProofOptions| {
+            proved.fetch_add(1, Ordering::SeqCst);
+            prove(token, pks, options)
+        };
+        let manifest = run_batch_with(manifest, &pks, &bucketed, &config, prove_counted).unwrap();
+        assert_eq!(proved.load(Ordering::SeqCst), 2);
+        let rebucketed = ProofOptions {
+            statements: Statements {
+                buckets: vec!["quantity:5000".parse::<BucketDefinition>().unwrap()],
+                ..Statements::default()
+            },
+            ..ProofOptions::default()
+        };
+        let manifest = run_batch_with(manifest, &pks, &rebucketed, &config, prove_counted).unwrap();
+        assert_eq!(proved.load(Ordering::SeqCst), 4);
+        // Nothing to do when nothing changed.
+        run_batch_with(manifest, &pks, &rebucketed, &config, prove_counted).unwrap();
+        assert_eq!(proved.load(Ordering::SeqCst), 4);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_batch_tokens_with_the_same_name() {
+        let dir = temp_dir();
+        for (subdir, token) in [("a", "first"), ("b", "second")] {
+            std::fs::create_dir_all(dir.join(subdir)).unwrap();
+            std::fs::write(dir.join(subdir).join("pod.jwt"), token).unwrap();
+        }
+        let manifest = BatchManifest {
+            entries: vec![
+                BatchEntry::new(dir.join("a").join("pod.jwt")),
+                BatchEntry::new(dir.join("b").join("pod.jwt")),
+            ],
+            ..BatchManifest::default()
+        };
+        let config = BatchConfig {
+            out_dir: dir.join("receipts"),
+            manifest_path: dir.join(MANIFEST_FILE_NAME),
+            concurrency: 1,
+        };
+
+        let manifest = run_batch_with(
+            manifest,
+            &[PK.to_string()],
+            &ProofOptions::default(),
+            &config,
+            |token, _, _| Ok(token.as_bytes().to_vec()),
+        )
+        .unwrap();
+        let receipts: Vec<Vec<u8>> = manifest
+            .entries
+            .iter()
+            .map(|entry| std::fs::read(entry.receipt.as_ref().unwrap()).unwrap())
+            .collect();
+        assert_eq!(receipts, [b"first".to_vec(), b"second".to_vec()]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,59 @@
+//! Cycle counts of the guest for key sets of different sizes.
+
+use crate::{count_cycles, count_cycles_trying_each_key, Error, ProofOptions};
+use jwt_compact::{
+    alg::{ModulusBits, Rsa},
+    jwk::JsonWebKey,
+};
+use rand_core::OsRng;
+
+/// User cycles taken with the signing key at one position in a key set.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CycleCount {
+    pub keys: usize,
+    pub signer_position: usize,
+    pub cycles: u64,
+    /// Cycles taken by the previous guest, which tried each key in turn.
+    pub try_each_key_cycles: u64,
+}
+
+/// Freshly generated RSA public keys that did not sign anything.
+pub fn decoy_keys(count: usize) -> Result<Vec<String>, Error> {
+    (0..count)
+        .map(|_| {
+            let (_, public_key) = Rsa::generate(&mut OsRng, ModulusBits::TwoKibibytes)
+                .map_err(|e| Error::BadInput(format!("Could not generate decoy key: {e}")))?;
+            let jwk = serde_json::to_string(&JsonWebKey::from(public_key.as_ref()))?;
+            Ok(jwk)
+        })
+        .collect()
+}
+
+/// Runs the guest, and the previous guest that tried each key in turn, for
+/// each key set size, with the signing key first and last among the decoys.
+pub fn run_cycle_bench(
+    token: &str,
+    signing_key: &str,
+    key_counts: &[usize],
+) -> Result<Vec<CycleCount>, Error> {
+    let largest = key_counts.iter().copied().max().unwrap_or(0);
+    let decoys = decoy_keys(largest.saturating_sub(1))?;
+
+    let mut counts = Vec::new();
+    for &keys in key_counts.iter().filter(|&&keys| keys > 0) {
+        for signer_position in [0, keys - 1] {
+            let mut pks = decoys[..keys - 1].to_vec();
+            pks.insert(signer_position, signing_key.to_string());
+            counts.push(CycleCount {
+                keys,
+                signer_position,
+                cycles: count_cycles(token, &pks, &ProofOptions::default())?,
+                try_each_key_cycles: count_cycles_trying_each_key(token, &pks)?,
+            });
+            if keys == 1 {
+                break;
+            }
+        }
+    }
+    Ok(counts)
+}
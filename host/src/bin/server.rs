@@ -0,0 +1,63 @@
+use clap::Parser;
+use host::server::{start, ServerConfig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Local HTTP service for proving and verifying Zero-Knowledge Proofs of Delivery
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: String,
+
+    /// Directory in which jobs and receipts are persisted
+    #[arg(long, default_value = "./zkpod-jobs")]
+    state_dir: PathBuf,
+
+    /// Number of proofs to compute concurrently
+    #[arg(long, default_value_t = 1)]
+    workers: usize,
+
+    /// Maximum number of queued proving jobs
+    #[arg(long, default_value_t = 16)]
+    queue_size: usize,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = Args::parse();
+
+    let router = match start(ServerConfig {
+        state_dir: args.state_dir,
+        workers: args.workers,
+        queue_size: args.queue_size,
+    }) {
+        Ok(router) => router,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+
+    let listener = match tokio::net::TcpListener::bind(&args.bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error: Could not listen on {}: {e}", args.bind);
+            return ExitCode::from(host::cli::EXIT_IO_ERROR);
+        }
+    };
+    println!("Listening on {}", args.bind);
+
+    match axum::serve(listener, router).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(host::cli::EXIT_IO_ERROR)
+        }
+    }
+}
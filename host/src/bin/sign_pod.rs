@@ -1,38 +1,7 @@
-use jwt_core::{CustomClaims, Issuer};
-use std::fs::File;
-use std::io::prelude::*;
+use clap::Parser;
+use host::cli::{main_with, Command, SignArgs};
+use std::process::ExitCode;
 
-fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-
-    let args: Vec<String> = std::env::args().collect();
-
-    if args.len() < 4 {
-        panic!("Usage: gen /path/to/issuer_secret_key.json /path/to/custom_claims.json /path/to/token.jwt");
-    }
-
-    let mut f = std::fs::File::open(&args[1])
-        .expect("Please provide issuer secret key in PEM format as first argument");
-    let mut secret_key = "".to_string();
-    f.read_to_string(&mut secret_key).unwrap();
-
-    let mut f = std::fs::File::open(&args[2])
-        .expect("Please provide custom claims in JSON file as second argument");
-    let mut claims_string = "".to_string();
-    f.read_to_string(&mut claims_string).unwrap();
-    let claims: CustomClaims =
-        serde_json::from_str(&claims_string).expect("Could not parse custom claims");
-
-    let iss = secret_key
-        .parse::<Issuer>()
-        .expect("failed to create issuer from secret key");
-    let token = iss
-        .generate_token(&claims)
-        .expect("failed to generate token");
-
-    let mut f = File::create(&args[3]).expect("Could not create JWT file");
-    f.write_all(&token.as_bytes())
-        .expect("Could not write to file");
+fn main() -> ExitCode {
+    main_with(Command::Sign(SignArgs::parse()))
 }
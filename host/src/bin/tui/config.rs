@@ -0,0 +1,203 @@
+//! Settings of the TUI, read from `zkpod/tui.json` in the user's config
+//! directory or from the file given with `--config`, and the form values of
+//! the last session, kept next to it.
+
+use host::key_set::LoadedKey;
+use jwt_core::CustomClaims;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::DEFAULT_VERIFICATION_KEYS;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub paths: DefaultPaths,
+    /// Named lists of verification key paths for the prove form.
+    pub key_sets: BTreeMap<String, Vec<String>>,
+    /// The key set the prove form starts with.
+    pub key_set: String,
+    /// Proves with fake receipts, by setting `RISC0_DEV_MODE`, unless the
+    /// environment already sets it either way.
+    pub dev_mode: bool,
+    /// Named claims the claims editor can start from.
+    pub claim_templates: BTreeMap<String, CustomClaims>,
+    /// Restores the forms as they were when the TUI last quit.
+    pub restore_session: bool,
+}
+
+/// Paths the forms start with.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DefaultPaths {
+    pub signing_key: String,
+    pub token: String,
+    pub receipt: String,
+    pub claims_file: String,
+    pub report: String,
+}
+
+impl Default for DefaultPaths {
+    fn default() -> Self {
+        DefaultPaths {
+            signing_key: "./test_data/coffee_company_sk.jwk".to_string(),
+            token: "./proof_of_delivery.jwt".to_string(),
+            receipt: "./zkpod.bin".to_string(),
+            claims_file: "./invoice_claims.json".to_string(),
+            report: "./zkpod_report.json".to_string(),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            paths: DefaultPaths::default(),
+            key_sets: BTreeMap::from([(
+                "test".to_string(),
+                DEFAULT_VERIFICATION_KEYS.map(str::to_string).to_vec(),
+            )]),
+            key_set: "test".to_string(),
+            dev_mode: false,
+            claim_templates: BTreeMap::new(),
+            restore_session: false,
+        }
+    }
+}
+
+impl Config {
+    /// `zkpod/tui.json` in the user's config directory, if there is one.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("zkpod").join("tui.json"))
+    }
+
+    /// Reads the config at `path`, or the defaults if there is no file.
+    pub fn load(path: &Path) -> Result<Self, host::Error> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| host::Error::io(format!("Could not read {}", path.display()), e))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// The paths of the key set named `name`, if the config has it.
+    pub fn key_set_paths(&self, name: &str) -> &[String] {
+        self.key_sets.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Turns on dev mode for proving, unless the environment already
+    /// decides.  Must be called before any thread is started.
+    pub fn apply_dev_mode(&self) {
+        if self.dev_mode && std::env::var_os("RISC0_DEV_MODE").is_none() {
+            std::env::set_var("RISC0_DEV_MODE", "1");
+        }
+    }
+}
+
+/// The next of `names` after `current`, wrapping around.
+pub fn next_name<'a>(
+    mut names: impl Iterator<Item = &'a String> + Clone,
+    current: Option<&str>,
+) -> Option<&'a String> {
+    let first = names.clone().next();
+    match current {
+        Some(current) => names
+            .position(|name| name == current)
+            .and_then(|_| names.next())
+            .or(first),
+        None => first,
+    }
+}
+
+/// Form values when the TUI last quit.  A form that has changed shape since
+/// is left with its defaults.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Session {
+    pub gen: Vec<String>,
+    pub claims_form: Vec<String>,
+    pub claims: Option<CustomClaims>,
+    pub prove: Vec<String>,
+    pub key_set: String,
+    pub prove_keys: Option<Vec<LoadedKey>>,
+    pub verify: Vec<String>,
+}
+
+impl Session {
+    /// Where the session of the config at `config_path` is kept.
+    pub fn path(config_path: &Path) -> PathBuf {
+        config_path.with_extension("session.json")
+    }
+
+    /// Reads the session at `path`, or an empty one if there is no file.
+    pub fn load(path: &Path) -> Result<Self, host::Error> {
+        if !path.exists() {
+            return Ok(Session::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| host::Error::io(format!("Could not read {}", path.display()), e))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), host::Error> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| host::Error::io(format!("Could not create {}", dir.display()), e))?;
+        }
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+            .map_err(|e| host::Error::io(format!("Could not write {}", path.display()), e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_partial_config() {
+        let config: Config =
+            serde_json::from_str(r#"{"dev_mode": true, "paths": {"receipt": "./a.bin"}}"#).unwrap();
+        assert!(config.dev_mode);
+        assert_eq!(config.paths.receipt, "./a.bin");
+        assert_eq!(config.paths.token, "./proof_of_delivery.jwt");
+        assert_eq!(config.key_set_paths(&config.key_set).len(), 3);
+        assert!(config.key_set_paths("missing").is_empty());
+
+        let missing = std::env::temp_dir().join("zkpod-missing-config.json");
+        assert!(Config::load(&missing).unwrap().claim_templates.is_empty());
+    }
+
+    #[test]
+    fn test_next_name() {
+        let names = ["a".to_string(), "b".to_string()];
+        assert_eq!(next_name(names.iter(), None).unwrap(), "a");
+        assert_eq!(next_name(names.iter(), Some("a")).unwrap(), "b");
+        assert_eq!(next_name(names.iter(), Some("b")).unwrap(), "a");
+        assert_eq!(next_name(names.iter(), Some("gone")).unwrap(), "a");
+        assert_eq!(next_name([].iter(), Some("a")), None);
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let path = Session::path(
+            &std::env::temp_dir()
+                .join(format!("zkpod-{}", std::process::id()))
+                .join("tui.json"),
+        );
+        assert!(path.ends_with("tui.session.json"));
+
+        let session = Session {
+            verify: vec!["./other.bin".to_string()],
+            prove_keys: Some(Vec::new()),
+            ..Session::default()
+        };
+        session.save(&path).unwrap();
+        let loaded = Session::load(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(loaded.verify, session.verify);
+        assert_eq!(loaded.prove_keys, Some(Vec::new()));
+        assert!(loaded.claims.is_none());
+    }
+}
@@ -0,0 +1,412 @@
+//! Popup for picking the file of a path field, with a preview of the
+//! selected file.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use host::deserialize_receipt;
+use host::key_set::load_keys;
+use jwt_core::{decode_untrusted_token, Issuer, Validator};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::percentage_area;
+
+/// Lines of a text file shown in the preview.
+const PREVIEW_LINES: usize = 20;
+
+/// Bytes read from a file to preview it, so that picking a large file does
+/// not read all of it.
+const PREVIEW_BYTES: u64 = 64 * 1024;
+
+/// Bytes read from a `.bin` file to check that it holds a receipt, which
+/// needs the whole of it.
+const RECEIPT_PREVIEW_BYTES: u64 = 8 * 1024 * 1024;
+
+/// What a path field refers to, which decides when its path is valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathKind {
+    /// An existing file to read.
+    Input,
+    /// A file to write, in an existing directory.
+    Output,
+    /// An existing file or directory, or nothing at all.
+    Optional,
+}
+
+/// The path a field holds and the file extensions it expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathSpec {
+    pub kind: PathKind,
+    pub extensions: &'static [&'static str],
+}
+
+impl PathSpec {
+    /// Why `value` is not a usable path, if it is not.
+    pub fn problem(&self, value: &str) -> Option<&'static str> {
+        let path = Path::new(value.trim());
+        match self.kind {
+            PathKind::Optional if value.trim().is_empty() => None,
+            PathKind::Optional if !path.exists() => Some("does not exist"),
+            PathKind::Optional => None,
+            PathKind::Input if !path.is_file() => Some("no such file"),
+            PathKind::Input => None,
+            PathKind::Output if value.trim().is_empty() || path.is_dir() => {
+                Some("needs a file name")
+            }
+            PathKind::Output => match path.parent() {
+                Some(parent) if parent.as_os_str().is_empty() || parent.is_dir() => None,
+                _ => Some("no such directory"),
+            },
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|extension| self.extensions.iter().any(|e| extension == *e))
+    }
+}
+
+/// What the user did in the file browser.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BrowserAction {
+    None,
+    Close,
+    Pick(PathBuf),
+}
+
+pub struct FileBrowser {
+    spec: PathSpec,
+    dir: PathBuf,
+    show_all: bool,
+    /// Subdirectories then files, after the parent directory if there is one.
+    entries: Vec<PathBuf>,
+    list_state: ListState,
+    error: Option<String>,
+    /// The entry [`Self::preview`] was worked out for, so that it is only
+    /// worked out again when the selection changes.
+    previewed: Option<PathBuf>,
+    preview: String,
+}
+
+impl FileBrowser {
+    /// Opens the browser in the directory of `current`, selecting it if it
+    /// is listed.
+    pub fn open(current: &str, spec: PathSpec) -> Self {
+        let current = Path::new(current.trim());
+        let dir = if current.is_dir() {
+            current.to_path_buf()
+        } else {
+            match current.parent() {
+                Some(parent) if parent.is_dir() => parent.to_path_buf(),
+                _ => PathBuf::from("."),
+            }
+        };
+        let mut browser = FileBrowser {
+            spec,
+            dir,
+            show_all: false,
+            entries: Vec::new(),
+            list_state: ListState::default(),
+            error: None,
+            previewed: None,
+            preview: String::new(),
+        };
+        browser.refresh();
+        if let Some(index) = browser.entries.iter().position(|entry| entry == current) {
+            browser.list_state.select(Some(index));
+        }
+        browser.update_preview();
+        browser
+    }
+
+    fn update_preview(&mut self) {
+        let selected = self.selected().cloned();
+        if selected != self.previewed || selected.is_none() {
+            self.preview = match &selected {
+                Some(path) => preview(path),
+                None => "No matching files".to_string(),
+            };
+            self.previewed = selected;
+        }
+    }
+
+    fn refresh(&mut self) {
+        let listing = std::fs::read_dir(&self.dir).and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        });
+        let (mut dirs, mut files): (Vec<_>, Vec<_>) = match listing {
+            Ok(paths) => {
+                self.error = None;
+                paths.into_iter().partition(|path| path.is_dir())
+            }
+            Err(e) => {
+                self.error = Some(format!("Could not read {}: {e}", self.dir.display()));
+                (Vec::new(), Vec::new())
+            }
+        };
+        files.retain(|file| self.show_all || self.spec.matches(file));
+        dirs.sort();
+        files.sort();
+
+        self.entries = self
+            .dir
+            .parent()
+            .map(|_| self.dir.join(".."))
+            .into_iter()
+            .collect();
+        self.entries.extend(dirs);
+        self.entries.extend(files);
+        self.list_state
+            .select((!self.entries.is_empty()).then_some(0));
+    }
+
+    fn enter(&mut self, dir: PathBuf) {
+        self.dir = std::fs::canonicalize(&dir).unwrap_or(dir);
+        self.refresh();
+    }
+
+    fn selected(&self) -> Option<&PathBuf> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.entries.get(index))
+    }
+
+    pub fn on_key_press(&mut self, event: KeyEvent) -> BrowserAction {
+        match event.code {
+            KeyCode::Esc => return BrowserAction::Close,
+            KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+            KeyCode::Left | KeyCode::Backspace => {
+                if self.dir.parent().is_some() {
+                    self.enter(self.dir.join(".."));
+                }
+            }
+            KeyCode::Char('a') => {
+                self.show_all = !self.show_all;
+                self.refresh();
+                // The file may have changed since it was previewed.
+                self.previewed = None;
+            }
+            KeyCode::Char('d') if self.spec.kind == PathKind::Optional => {
+                return BrowserAction::Pick(self.dir.clone());
+            }
+            KeyCode::Enter | KeyCode::Right => match self.selected().cloned() {
+                Some(path) if path.is_dir() => self.enter(path),
+                Some(path) if event.code == KeyCode::Enter => return BrowserAction::Pick(path),
+                _ => {}
+            },
+            _ => {}
+        }
+        self.update_preview();
+        BrowserAction::None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let area = percentage_area(frame.area(), 90, 80);
+        frame.render_widget(Clear, area);
+
+        let layout = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]);
+        let [list_area, preview_area] = area.layout(&layout);
+
+        let filter = if self.show_all {
+            "all files".to_string()
+        } else {
+            self.spec
+                .extensions
+                .iter()
+                .map(|extension| format!(".{extension}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let title = format!(
+            "{} ({filter}): <Enter> pick, <Backspace> up, <a> all files{}, <Esc> cancel",
+            self.dir.display(),
+            if self.spec.kind == PathKind::Optional {
+                ", <d> this directory"
+            } else {
+                ""
+            },
+        );
+        let items = self.entries.iter().map(|entry| {
+            let name = entry
+                .file_name()
+                .map_or("..".into(), |name| name.to_string_lossy());
+            if entry.is_dir() {
+                Line::from(Span::from(format!("{name}/")).fg(Color::Blue))
+            } else {
+                Line::from(name.to_string())
+            }
+        });
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Modifier::REVERSED)
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let text = match &self.error {
+            Some(error) => error.clone(),
+            None => self.preview.clone(),
+        };
+        let paragraph = Paragraph::new(text)
+            .block(Block::bordered().title("Preview"))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, preview_area);
+    }
+}
+
+/// A summary of the file at `path`: the keys of a JWK or JWKS file, the
+/// header and claims of a token, or whether a file holds a receipt.  Only
+/// the start of a large file is read, and then shown as text.
+pub fn preview(path: &Path) -> String {
+    if path.is_dir() {
+        return match std::fs::read_dir(path) {
+            Ok(entries) => format!("Directory with {} entries", entries.count()),
+            Err(e) => e.to_string(),
+        };
+    }
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let limit = match extension {
+        Some("bin") => RECEIPT_PREVIEW_BYTES,
+        _ => PREVIEW_BYTES,
+    };
+    let (bytes, size) = match read_start(path, limit) {
+        Ok(start) => start,
+        Err(e) => return e.to_string(),
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    if size > limit {
+        let lines = text.lines().take(PREVIEW_LINES).collect::<Vec<_>>();
+        return format!(
+            "File of {size} bytes, too large to preview in full\n\n{}",
+            lines.join("\n")
+        );
+    }
+    match extension {
+        Some("jwk" | "json") if text.contains("\"kty\"") => preview_keys(path),
+        Some("jwt") => match decode_untrusted_token(text.trim()) {
+            Ok((header, claims)) => format!(
+                "Token of type {}, signed with key {}\n\n{}",
+                header.token_type.as_deref().unwrap_or("none"),
+                header.key_id.as_deref().unwrap_or("(not named)"),
+                claims.custom.pretty_print()
+            ),
+            Err(e) => format!("Not a token: {e}"),
+        },
+        Some("bin") => match deserialize_receipt(&bytes) {
+            Ok(_) => format!("Receipt of {} bytes", bytes.len()),
+            Err(e) => format!("Not a receipt: {e}"),
+        },
+        _ => text
+            .lines()
+            .take(PREVIEW_LINES)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Reads at most `limit` bytes from the start of the file, and its size.
+fn read_start(path: &Path, limit: u64) -> std::io::Result<(Vec<u8>, u64)> {
+    let file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut bytes = Vec::new();
+    file.take(limit).read_to_end(&mut bytes)?;
+    Ok((bytes, size))
+}
+
+fn preview_keys(path: &Path) -> String {
+    let keys = match load_keys(path) {
+        Ok(keys) => keys,
+        Err(e) => return e.to_string(),
+    };
+    keys.iter()
+        .map(|key| {
+            let kind = if key.jwk.parse::<Issuer>().is_ok() {
+                "secret signing key".to_string()
+            } else {
+                match key.jwk.parse::<Validator>() {
+                    Ok(validator) => format!("public key {}", validator.thumbprint),
+                    Err(e) => format!("unusable key: {e}"),
+                }
+            };
+            format!("{}: {kind}", key.label)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const KEYS: PathSpec = PathSpec {
+        kind: PathKind::Input,
+        extensions: &["jwk"],
+    };
+
+    #[test]
+    fn test_path_problems() {
+        assert_eq!(KEYS.problem("../test_data/coffee_company_pk.jwk"), None);
+        assert!(KEYS.problem("../test_data/missing.jwk").is_some());
+        assert!(KEYS.problem("../test_data").is_some());
+
+        let output = PathSpec {
+            kind: PathKind::Output,
+            extensions: &["bin"],
+        };
+        assert_eq!(output.problem("zkpod.bin"), None);
+        assert_eq!(output.problem("../test_data/zkpod.bin"), None);
+        assert!(output.problem("../no_such_dir/zkpod.bin").is_some());
+        assert!(output.problem("").is_some());
+
+        let optional = PathSpec {
+            kind: PathKind::Optional,
+            extensions: &["jwk"],
+        };
+        assert_eq!(optional.problem(""), None);
+        assert_eq!(optional.problem("../test_data"), None);
+        assert!(optional.problem("../no_such_dir").is_some());
+    }
+
+    #[test]
+    fn test_browse_test_data() {
+        let mut browser = FileBrowser::open("../test_data/coffee_company_pk.jwk", KEYS);
+        assert_eq!(
+            browser.selected(),
+            Some(&PathBuf::from("../test_data/coffee_company_pk.jwk"))
+        );
+        assert!(browser
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_dir())
+            .all(|entry| KEYS.matches(entry)));
+        assert!(browser.preview.contains("public key"));
+        assert!(preview(Path::new("../test_data/coffee_company_sk.jwk")).contains("secret"));
+
+        browser.on_key_press(KeyEvent::from(KeyCode::Up));
+        assert_eq!(browser.previewed.as_ref(), browser.selected());
+        assert!(!browser.preview.contains("public key"));
+        browser.on_key_press(KeyEvent::from(KeyCode::Down));
+        assert!(browser.preview.contains("public key"));
+
+        let large = std::env::temp_dir().join(format!("zkpod-preview-{}.jwt", std::process::id()));
+        std::fs::write(&large, vec![b'a'; PREVIEW_BYTES as usize + 1]).unwrap();
+        let text = preview(&large);
+        std::fs::remove_file(&large).unwrap();
+        assert!(text.starts_with("File of 65537 bytes, too large"));
+
+        assert_eq!(
+            browser.on_key_press(KeyEvent::from(KeyCode::Enter)),
+            BrowserAction::Pick(PathBuf::from("../test_data/coffee_company_pk.jwk"))
+        );
+        assert_eq!(
+            browser.on_key_press(KeyEvent::from(KeyCode::Esc)),
+            BrowserAction::Close
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Screen showing what a proof of delivery holds and which of the prove
+//! form's keys signed it, before spending time on proving.
+
+use crossterm::event::KeyEvent;
+use host::inspect::TokenInspection;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::{percentage_area, scrolled};
+
+pub struct InspectView {
+    pub inspection: TokenInspection,
+    scroll: u16,
+}
+
+impl InspectView {
+    pub fn new(inspection: TokenInspection) -> Self {
+        InspectView {
+            inspection,
+            scroll: 0,
+        }
+    }
+
+    /// Scrolls the inspection, returning whether the key was a scrolling key.
+    pub fn on_key_press(&mut self, event: KeyEvent) -> bool {
+        match scrolled(self.scroll, event.code) {
+            Some(scroll) => {
+                self.scroll = scroll;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn details(&self) -> Text<'static> {
+        let inspection = &self.inspection;
+        let header_member = |member: &str| {
+            inspection
+                .header
+                .get(member)
+                .and_then(|value| value.as_str())
+                .unwrap_or("none")
+                .to_string()
+        };
+
+        let mut text = Text::from(Line::from("Header").bold().underlined());
+        text.push_line(format!("  Type  {}", header_member("typ")));
+        text.push_line(format!("  Key   {}", header_member("kid")));
+
+        text.push_line("");
+        text.push_line(Line::from("Claims").bold().underlined());
+        let width = inspection
+            .items
+            .iter()
+            .map(|item| item.key.len())
+            .max()
+            .unwrap_or(0);
+        for item in inspection.items.iter() {
+            let visibility = if item.is_private {
+                Span::from("private  ").fg(Color::Red)
+            } else {
+                Span::from("public   ").fg(Color::Green)
+            };
+            text.push_line(Line::from_iter([
+                Span::from("  "),
+                visibility,
+                Span::from(format!("{:width$}  ", item.key)).bold(),
+                Span::from(item.value.clone()),
+            ]));
+        }
+
+        text.push_line("");
+        text.push_line(Line::from("A receipt would disclose").bold().underlined());
+        let public_claims = serde_json::to_string_pretty(&inspection.public_claims)
+            .expect("a JSON map always serialises");
+        for line in public_claims.lines() {
+            text.push_line(format!("  {line}"));
+        }
+
+        text.push_line("");
+        text.push_line(Line::from("Candidate keys").bold().underlined());
+        for check in inspection.keys.iter() {
+            let status = match &check.error {
+                None => Span::from("signed the token").fg(Color::Green),
+                Some(e) => Span::from(e.clone()).fg(Color::Red),
+            };
+            text.push_line(Line::from_iter([
+                Span::from(format!("  {}  ", check.label)).bold(),
+                Span::from(format!(
+                    "{}  ",
+                    check.fingerprint.as_deref().unwrap_or("no fingerprint")
+                )),
+                status,
+            ]));
+        }
+        text
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = percentage_area(frame.area(), 90, 90);
+        frame.render_widget(Clear, area);
+
+        let layout = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]);
+        let [banner_area, details_area] = area.layout(&layout);
+
+        let (banner, color) = match self.inspection.signer {
+            Some(index) => (
+                format!(
+                    "Signed by {}: proving with these keys will succeed",
+                    self.inspection.keys[index].label
+                ),
+                Color::Green,
+            ),
+            None => (
+                "No candidate key verifies this token: proving would fail".to_string(),
+                Color::Red,
+            ),
+        };
+        let banner = Paragraph::new(Line::from(banner).bold().centered())
+            .block(Block::bordered())
+            .fg(color);
+        frame.render_widget(banner, banner_area);
+
+        let details = Paragraph::new(self.details())
+            .block(Block::bordered().title(
+                "Proof of delivery: <Up>/<Down>/<PgUp>/<PgDn> scroll, <Esc> back to the prove form",
+            ))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(details, details_area);
+    }
+}
@@ -0,0 +1,1523 @@
+// Adapted from
+// https://github.com/ratatui/ratatui/blob/9713828c838d567ec4d782869f1e2f267cc022b3/ratatui-widgets/examples/list.rs
+// with the following licence
+
+// The MIT License (MIT)
+
+// Copyright (c) 2016-2022 Florian Dehau
+// Copyright (c) 2023-2025 The Ratatui Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # [Ratatui] `List` example
+//!
+//! The latest version of this example is available in the [widget examples] folder in the
+//! repository.
+//!
+//! Please note that the examples are designed to be run against the `main` branch of the Github
+//! repository. This means that you may not be able to compile with the latest release version on
+//! crates.io, or the one that you have installed locally.
+//!
+//! See the [examples readme] for more information on finding examples that match the version of the
+//! library you are using.
+//!
+//! [Ratatui]: https://github.com/ratatui/ratatui
+//! [widget examples]: https://github.com/ratatui/ratatui/blob/main/ratatui-widgets/examples
+//! [examples readme]: https://github.com/ratatui/ratatui/blob/main/examples/README.md
+
+mod config;
+mod file_browser;
+mod inspect_view;
+mod report_view;
+mod string_field;
+
+use chrono::{Local, NaiveDate};
+use clap::Parser;
+use color_eyre::Result;
+use config::{next_name, Config, DefaultPaths, Session};
+use crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyModifiers,
+};
+use crossterm::execute;
+use file_browser::{BrowserAction, FileBrowser, PathKind};
+use host::inspect::TokenInspection;
+use host::invoice::{parse_line_items, CarrierReceipt, Invoice, DEFAULT_DISCLOSURE};
+use host::key_set::{load_keys, LoadedKey};
+use host::report::VerificationReport;
+use host::{compute_fingerprint, prove_token_validation_with, serialize_receipt, ProofOptions};
+use inspect_view::InspectView;
+use jwt_core::statement::DELIVERY_DATE_CLAIM;
+use jwt_core::{
+    BucketDefinition, ClaimItem, CustomClaims, DateWindow, Issuer, Statements, Validator,
+};
+use ratatui::layout::{Constraint, Flex, Layout, Margin, Rect};
+use ratatui::style::{Color, Modifier, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, List, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use report_view::ReportView;
+use std::path::{Path, PathBuf};
+use string_field::{Check, StringField};
+
+/// Verification keys of the default config's key set.
+const DEFAULT_VERIFICATION_KEYS: [&str; 3] = [
+    "./test_data/other_pk_1.jwk",
+    "./test_data/coffee_company_pk.jwk",
+    "./test_data/other_pk_2.jwk",
+];
+
+/// Terminal interface for the Zero-Knowledge Proof of Delivery demo
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+struct Args {
+    /// Config file to use, e.g. one per project [default: zkpod/tui.json in
+    /// the user's config directory]
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+
+    let config_path = args.config.or_else(Config::default_path);
+    let config = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    config.apply_dev_mode();
+
+    let mut app = App::new(config);
+    if let Some(path) = config_path.filter(|_| app.config.restore_session) {
+        app.load_session(Session::path(&path));
+    }
+
+    match ratatui::run(|terminal| {
+        execute!(std::io::stdout(), EnableBracketedPaste)?;
+        let result = app.run(terminal);
+        execute!(std::io::stdout(), DisableBracketedPaste)?;
+        result
+    }) {
+        Ok(()) => println!("Exited"),
+        Err(err) => eprintln!("{err}"),
+    }
+    Ok(())
+}
+
+pub struct App {
+    state: AppState,
+    window: AppWindow,
+    home: SelectScreen,
+    gen_and_sign_form: InputForm,
+    claims_editor: ClaimsEditor,
+    prove_form: InputForm,
+    prove_keys: KeyList,
+    inspect_view: Option<InspectView>,
+    verify_form: InputForm,
+    report_view: Option<ReportView>,
+    popup: Option<Popup>,
+    file_browser: Option<FileBrowser>,
+    config: Config,
+    /// Where the form values are saved on quitting, if they are restored.
+    session_path: Option<PathBuf>,
+}
+
+#[derive(PartialEq, Eq)]
+enum AppState {
+    Running,
+    Cancelled,
+    Submitted,
+}
+
+#[derive(PartialEq, Eq)]
+enum AppWindow {
+    Home,
+    Gen,
+    Claims,
+    Prove,
+    Inspect,
+    Verify,
+    Report,
+}
+
+/// A message shown over the current window until a key is pressed.
+struct Popup {
+    title: &'static str,
+    text: String,
+    is_error: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App::new(Config::default())
+    }
+}
+
+impl App {
+    fn new(config: Config) -> Self {
+        let paths = &config.paths;
+        let gen_and_sign_form = InputForm {
+            fields: Vec::from([
+                StringField::new("Issuer ID", "Coffee Chain 1".to_owned()),
+                StringField::new("Supplier ID", "Coffee Supplier".to_owned()),
+                StringField::new(
+                    "Line items (product:quantity:unit:unit price; ...)",
+                    "raw coffee beans:1000:kg:4.00 GBP".to_owned(),
+                )
+                .with_check(Check::LineItems),
+                StringField::new("Disclosed line item fields", DEFAULT_DISCLOSURE.to_owned())
+                    .with_check(Check::Disclosure),
+                StringField::new("Path to signing key", paths.signing_key.clone())
+                    .with_path(PathKind::Input, &["jwk"]),
+                StringField::new("Path to output proof of delivery", paths.token.clone())
+                    .with_path(PathKind::Output, &["jwt"]),
+                StringField::new(
+                    "Template (invoice or carrier-receipt)",
+                    "invoice".to_owned(),
+                )
+                .with_check(Check::OneOf(&["invoice", "carrier-receipt"])),
+                StringField::new(
+                    "Invoice reference (carrier receipt: Issuer ID is the recipient, Supplier ID the carrier)",
+                    "".to_owned(),
+                ),
+                StringField::new(
+                    "Delivery date (YYYY-MM-DD)",
+                    Local::now().date_naive().to_string(),
+                )
+                .with_check(Check::Date),
+            ]),
+            focus: 0,
+        };
+
+        let claims_editor = ClaimsEditor::new(
+            gen_form_claims(&gen_and_sign_form.get_form_fields()).unwrap_or_default(),
+            paths,
+        );
+
+        let prove_form = InputForm {
+            fields: Vec::from([
+                StringField::new("Path to proof of delivery", paths.token.clone())
+                    .with_path(PathKind::Input, &["jwt"]),
+                StringField::new(
+                    "Path to output Zero-Knowledge Proof of Delivery",
+                    paths.receipt.clone(),
+                )
+                .with_path(PathKind::Output, &["bin"]),
+                StringField::new(
+                    "Bucketed claims (claim:boundary,boundary; ...)",
+                    "".to_owned(),
+                )
+                .with_check(Check::Buckets),
+                StringField::new(
+                    "Delivered within (e.g. 12m for the past year; empty for any date)",
+                    "".to_owned(),
+                )
+                .with_check(Check::Period),
+                StringField::new(
+                    "Add verification keys (.jwk, JWKS file or directory)",
+                    "".to_owned(),
+                )
+                .with_path(PathKind::Optional, &["jwk", "json"]),
+            ]),
+            focus: 0,
+        };
+
+        let verify_form = InputForm {
+            fields: Vec::from([StringField::new(
+                "Path to Proof of Delivery",
+                paths.receipt.clone(),
+            )
+            .with_path(PathKind::Input, &["bin"])]),
+            focus: 0,
+        };
+
+        App {
+            state: AppState::Running,
+            window: AppWindow::Home,
+            home: SelectScreen::default(),
+            gen_and_sign_form,
+            claims_editor,
+            prove_form,
+            prove_keys: KeyList::from_set(&config, &config.key_set),
+            inspect_view: None,
+            verify_form,
+            report_view: None,
+            popup: None,
+            file_browser: None,
+            config,
+            session_path: None,
+        }
+    }
+
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while self.state != AppState::Cancelled {
+            while self.state != AppState::Submitted {
+                terminal.draw(|frame: &mut Frame<'_>| self.render(frame))?;
+                self.handle_events()?;
+                if self.state == AppState::Cancelled {
+                    return self.quit();
+                }
+            }
+            // Now submitted
+            if let Err(e) = self.submit() {
+                self.show_error(&e);
+            }
+            self.state = AppState::Running;
+        }
+        self.quit()
+    }
+
+    /// Saves the form values for the next session, if they are restored.
+    fn quit(&self) -> Result<()> {
+        if let Some(path) = &self.session_path {
+            self.session().save(path)?;
+        }
+        Ok(())
+    }
+
+    fn session(&self) -> Session {
+        Session {
+            gen: self.gen_and_sign_form.get_form_fields(),
+            claims_form: self.claims_editor.form.get_form_fields(),
+            claims: Some(self.claims_editor.claims.clone()),
+            prove: self.prove_form.get_form_fields(),
+            key_set: self.prove_keys.set.clone(),
+            prove_keys: Some(self.prove_keys.loaded_keys()),
+            verify: self.verify_form.get_form_fields(),
+        }
+    }
+
+    /// Restores the session saved at `path`, and saves the next one there.
+    /// A session that cannot be read is ignored with a warning, so that it
+    /// does not keep the TUI from starting.
+    fn load_session(&mut self, path: PathBuf) {
+        match Session::load(&path) {
+            Ok(session) => self.restore(session),
+            Err(e) => {
+                self.popup = Some(Popup {
+                    title: "Warning (press any key to continue)",
+                    text: format!(
+                        "Ignored the saved session, starting from the defaults instead; it will be overwritten on quitting. {e}"
+                    ),
+                    is_error: false,
+                })
+            }
+        }
+        self.session_path = Some(path);
+    }
+
+    fn restore(&mut self, session: Session) {
+        self.gen_and_sign_form.restore(session.gen);
+        self.claims_editor.form.restore(session.claims_form);
+        if let Some(claims) = session.claims {
+            self.claims_editor.replace(claims);
+        }
+        self.prove_form.restore(session.prove);
+        if let Some(keys) = session.prove_keys {
+            self.prove_keys = KeyList {
+                set: session.key_set,
+                ..KeyList::default()
+            };
+            self.prove_keys.extend(keys);
+        }
+        self.verify_form.restore(session.verify);
+    }
+
+    /// Carries out the form of the current window.  The window is left as
+    /// it is on failure, so the inputs can be corrected.
+    fn submit(&mut self) -> std::result::Result<(), host::Error> {
+        match self.window {
+            AppWindow::Home | AppWindow::Inspect => (),
+            AppWindow::Gen => {
+                let args = self.gen_and_sign_form.get_form_fields();
+
+                let claims = gen_form_claims(&args)?;
+                let is_delivery_receipt = args[6].trim() == "carrier-receipt";
+                sign_claims(&claims, &args[4], &args[5], is_delivery_receipt)?;
+                self.window = AppWindow::Home;
+            }
+            AppWindow::Claims => {
+                let args = self.claims_editor.form.get_form_fields();
+
+                sign_claims(
+                    &self.claims_editor.claims,
+                    &args[SIGNING_KEY_FIELD],
+                    &args[TOKEN_FIELD],
+                    false,
+                )?;
+                self.window = AppWindow::Home;
+            }
+            AppWindow::Prove => {
+                let args = self.prove_form.get_form_fields();
+
+                let token = read_file(&args[0])?;
+                let pks = self.prove_keys.jwks()?;
+
+                let buckets = args[2]
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|bucket| !bucket.is_empty())
+                    .map(str::parse::<BucketDefinition>)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let date_windows = match args[3].trim() {
+                    "" => Vec::new(),
+                    period => vec![DateWindow::trailing(
+                        DELIVERY_DATE_CLAIM,
+                        period,
+                        Local::now().date_naive(),
+                    )?],
+                };
+                let options = ProofOptions {
+                    statements: Statements {
+                        buckets,
+                        date_windows,
+                        ..Statements::default()
+                    },
+                    ..ProofOptions::default()
+                };
+
+                let receipt = prove_token_validation_with(token.trim(), &pks, &options)?;
+                write_file(&args[1], &serialize_receipt(&receipt)?)?;
+                self.window = AppWindow::Home;
+            }
+            AppWindow::Verify => {
+                let args = self.verify_form.get_form_fields();
+
+                let receipt = std::fs::read(args[0].trim())
+                    .map_err(|e| host::Error::io(format!("Could not read {}", args[0]), e))?;
+                let labels = self
+                    .prove_keys
+                    .entries
+                    .iter()
+                    .filter_map(|entry| Some((entry.fingerprint.clone()?, entry.label.clone())))
+                    .collect();
+                self.report_view = Some(ReportView::new(
+                    VerificationReport::from_receipt_bytes(&receipt),
+                    args[0].trim().to_string(),
+                    labels,
+                    &self.config.paths.report,
+                ));
+                self.window = AppWindow::Report;
+            }
+            AppWindow::Report => {
+                if let Some(report_view) = &self.report_view {
+                    let path = report_view.save_path();
+                    let json = serde_json::to_string_pretty(&report_view.report)?;
+                    write_file(&path, json.as_bytes())?;
+                    self.popup = Some(Popup {
+                        title: "Saved",
+                        text: format!("Saved the report to {}", path.trim()),
+                        is_error: false,
+                    });
+                }
+            }
+        };
+        Ok(())
+    }
+
+    fn show_error(&mut self, error: &host::Error) {
+        self.popup = Some(Popup {
+            title: "Error (press any key to return)",
+            text: error.to_string(),
+            is_error: true,
+        });
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        match self.window {
+            AppWindow::Home => self.home.render(frame),
+            AppWindow::Gen => {
+                self.gen_and_sign_form.render(frame);
+            }
+            AppWindow::Claims => self.claims_editor.render(frame),
+            AppWindow::Prove => {
+                let rest = self.prove_form.render(frame);
+                self.prove_keys.render(frame, rest);
+            }
+            AppWindow::Verify => {
+                self.verify_form.render(frame);
+            }
+            AppWindow::Inspect => {
+                if let Some(inspect_view) = &self.inspect_view {
+                    inspect_view.render(frame);
+                }
+            }
+            AppWindow::Report => {
+                if let Some(report_view) = &mut self.report_view {
+                    report_view.render(frame);
+                }
+            }
+        };
+        if let Some(file_browser) = &mut self.file_browser {
+            file_browser.render(frame);
+        }
+        if let Some(popup) = &self.popup {
+            popup.render(frame);
+        }
+    }
+
+    /// The form shown in the current window, if any.
+    fn current_form(&mut self) -> Option<&mut InputForm> {
+        match self.window {
+            AppWindow::Gen => Some(&mut self.gen_and_sign_form),
+            AppWindow::Claims => Some(&mut self.claims_editor.form),
+            AppWindow::Prove => Some(&mut self.prove_form),
+            AppWindow::Verify => Some(&mut self.verify_form),
+            AppWindow::Report => self
+                .report_view
+                .as_mut()
+                .map(|report_view| &mut report_view.form),
+            AppWindow::Home | AppWindow::Inspect => None,
+        }
+    }
+
+    /// Passes a key to the open file browser, filling in the focused field
+    /// with the path picked.
+    fn on_file_browser_key_press(&mut self, event: KeyEvent) {
+        let Some(file_browser) = &mut self.file_browser else {
+            return;
+        };
+        match file_browser.on_key_press(event) {
+            BrowserAction::None => return,
+            BrowserAction::Close => {}
+            BrowserAction::Pick(path) => {
+                if let Some(form) = self.current_form() {
+                    form.fields[form.focus].set_value(path.display().to_string());
+                }
+            }
+        }
+        self.file_browser = None;
+    }
+
+    /// Pastes into the focused field of the current form, if any.
+    fn on_paste(&mut self, text: &str) {
+        if self.popup.is_some() || self.file_browser.is_some() {
+            return;
+        }
+        if let Some(form) = self.current_form() {
+            form.fields[form.focus].paste(text);
+        }
+        if self.window == AppWindow::Claims {
+            self.claims_editor.sync_value_check();
+        }
+    }
+
+    fn handle_events(&mut self) -> Result<Vec<String>> {
+        let event = event::read()?;
+        if let Event::Paste(text) = &event {
+            self.on_paste(text);
+        }
+        if let Some(key) = event.as_key_press_event() {
+            if self.popup.is_some() {
+                self.popup = None;
+                return Ok(Vec::new());
+            }
+            if self.file_browser.is_some() {
+                self.on_file_browser_key_press(key);
+                return Ok(Vec::new());
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter if self.window == AppWindow::Inspect => {
+                    self.window = AppWindow::Prove;
+                }
+                KeyCode::Esc => {
+                    self.state = match self.window {
+                        AppWindow::Home => AppState::Cancelled,
+                        _ => {
+                            self.window = AppWindow::Home;
+                            AppState::Running
+                        }
+                    }
+                }
+                KeyCode::Enter if self.window == AppWindow::Home => {
+                    if let Some(result) = self.home.on_key_press(key) {
+                        self.window = match result {
+                            0 => AppWindow::Gen,
+                            1 => AppWindow::Claims,
+                            2 => AppWindow::Prove,
+                            3 => AppWindow::Verify,
+                            _ => AppWindow::Home,
+                        }
+                    }
+                }
+                KeyCode::Enter => {
+                    if let Some(form) = self.current_form() {
+                        match form.first_invalid_field() {
+                            Some(index) => form.focus = index,
+                            None => self.state = AppState::Submitted,
+                        }
+                    }
+                }
+                KeyCode::Char('o') if key.modifiers == KeyModifiers::CONTROL => {
+                    if let Some(form) = self.current_form() {
+                        let field = &form.fields[form.focus];
+                        self.file_browser =
+                            field.path.map(|spec| FileBrowser::open(&field.value, spec));
+                    }
+                }
+                _ => match self.window {
+                    AppWindow::Gen => self.gen_and_sign_form.on_key_press(key),
+                    AppWindow::Claims => {
+                        if !self.on_claims_editor_key_press(key) {
+                            self.claims_editor.form.on_key_press(key);
+                            self.claims_editor.sync_value_check();
+                        }
+                    }
+                    AppWindow::Prove => {
+                        if !self.on_key_list_key_press(key) {
+                            self.prove_form.on_key_press(key)
+                        }
+                    }
+                    AppWindow::Verify => self.verify_form.on_key_press(key),
+                    AppWindow::Inspect => {
+                        if let Some(inspect_view) = &mut self.inspect_view {
+                            inspect_view.on_key_press(key);
+                        }
+                    }
+                    AppWindow::Report => {
+                        if let Some(report_view) = &mut self.report_view {
+                            if !report_view.on_key_press(key) {
+                                report_view.form.on_key_press(key)
+                            }
+                        }
+                    }
+                    AppWindow::Home => {
+                        if self.home.on_key_press(key) == Some(410) {
+                            self.state = AppState::Cancelled;
+                        };
+                    }
+                },
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Handles the key bindings of the prove form's key list, returning
+    /// whether the key was one of them.
+    fn on_key_list_key_press(&mut self, event: KeyEvent) -> bool {
+        match (event.code, event.modifiers) {
+            (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                let field = &mut self.prove_form.fields[KEY_PATH_FIELD];
+                match self.prove_keys.add(field.value.trim()) {
+                    Ok(()) => field.set_value(String::new()),
+                    Err(e) => self.show_error(&e),
+                }
+            }
+            (KeyCode::Char('x'), KeyModifiers::CONTROL) => self.prove_keys.remove_selected(),
+            (KeyCode::Char('k'), KeyModifiers::CONTROL) => {
+                match next_name(self.config.key_sets.keys(), Some(&self.prove_keys.set)) {
+                    Some(name) => self.prove_keys = KeyList::from_set(&self.config, name),
+                    None => self.show_error(&host::Error::BadInput(
+                        "The config file has no key sets".to_string(),
+                    )),
+                }
+            }
+            (KeyCode::Char('t'), KeyModifiers::CONTROL) => match self.inspect_token() {
+                Ok(inspection) => {
+                    self.inspect_view = Some(InspectView::new(inspection));
+                    self.window = AppWindow::Inspect;
+                }
+                Err(e) => self.show_error(&e),
+            },
+            (KeyCode::PageDown, _) => self.prove_keys.list_state.select_next(),
+            (KeyCode::PageUp, _) => self.prove_keys.list_state.select_previous(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Inspects the prove form's token against its key list.
+    fn inspect_token(&self) -> std::result::Result<TokenInspection, host::Error> {
+        let token = read_file(&self.prove_form.fields[0].value)?;
+        TokenInspection::new(&token, &self.prove_keys.loaded_keys())
+    }
+
+    /// Handles the key bindings of the claims editor, returning whether the
+    /// key was one of them.
+    fn on_claims_editor_key_press(&mut self, event: KeyEvent) -> bool {
+        if event.modifiers != KeyModifiers::CONTROL {
+            return match event.code {
+                KeyCode::PageDown => {
+                    self.claims_editor.list_state.select_next();
+                    true
+                }
+                KeyCode::PageUp => {
+                    self.claims_editor.list_state.select_previous();
+                    true
+                }
+                _ => false,
+            };
+        }
+
+        let editor = &mut self.claims_editor;
+        let path = editor.form.fields[CLAIMS_FILE_FIELD]
+            .value
+            .trim()
+            .to_string();
+        let result = match event.code {
+            KeyCode::Char('n') => editor.set_claim(),
+            KeyCode::Char('e') => {
+                editor.edit_selected();
+                Ok(())
+            }
+            KeyCode::Char('x') => {
+                editor.remove_selected();
+                Ok(())
+            }
+            KeyCode::Char('p') => {
+                editor.toggle_private();
+                Ok(())
+            }
+            KeyCode::Char('u') => {
+                editor.move_selected(true);
+                Ok(())
+            }
+            KeyCode::Char('d') => {
+                editor.move_selected(false);
+                Ok(())
+            }
+            KeyCode::Char('l') => editor.load(Path::new(&path)),
+            KeyCode::Char('s') => editor.save(Path::new(&path)).map(|()| {
+                self.popup = Some(Popup {
+                    title: "Saved",
+                    text: format!("Saved the claims to {path}"),
+                    is_error: false,
+                });
+            }),
+            KeyCode::Char('t') => {
+                gen_form_claims(&self.gen_and_sign_form.get_form_fields()).map(|claims| {
+                    editor.replace(claims);
+                    editor.template = None;
+                })
+            }
+            KeyCode::Char('g') => {
+                let templates = &self.config.claim_templates;
+                match next_name(templates.keys(), editor.template.as_deref()) {
+                    Some(name) => {
+                        editor.replace(templates[name].clone());
+                        editor.template = Some(name.clone());
+                        Ok(())
+                    }
+                    None => Err(host::Error::BadInput(
+                        "The config file has no claim templates".to_string(),
+                    )),
+                }
+            }
+            _ => return false,
+        };
+        if let Err(e) = result {
+            self.show_error(&e);
+        }
+        true
+    }
+}
+
+impl Popup {
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+
+        let mut block = Block::bordered().title(self.title);
+        if self.is_error {
+            block = block.fg(Color::Red);
+        }
+        let area = percentage_area(area, 80, 80);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let result = percentage_area(area, 90, 90);
+
+        let text: Text = self.text.split('\n').collect();
+        let text = if self.is_error {
+            text
+        } else {
+            text.slow_blink()
+        };
+        let paragraph = Paragraph::new(text).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, result);
+    }
+}
+
+/// Builds the claims described by the fields of the generate form.
+fn gen_form_claims(args: &[String]) -> std::result::Result<CustomClaims, host::Error> {
+    let line_items = parse_line_items(&args[2])?;
+    let disclosure = args[3].parse()?;
+    let delivery_date = args[8]
+        .trim()
+        .parse::<NaiveDate>()
+        .map_err(|e| host::Error::BadInput(format!("Could not parse delivery date: {e}")))?;
+    Ok(match args[6].trim() {
+        "carrier-receipt" => CarrierReceipt {
+            carrier_id: args[1].clone(),
+            recipient_id: args[0].clone(),
+            reference: args[7].trim().to_string(),
+            line_items,
+            disclosure,
+            delivery_date,
+        }
+        .to_claims()?,
+        _ => Invoice {
+            issuer_id: args[0].clone(),
+            subject_id: args[1].clone(),
+            line_items,
+            disclosure,
+            delivery_date,
+        }
+        .to_claims()?,
+    })
+}
+
+fn read_file(path: &str) -> std::result::Result<String, host::Error> {
+    std::fs::read_to_string(path.trim())
+        .map_err(|e| host::Error::io(format!("Could not read {path}"), e))
+}
+
+fn write_file(path: &str, contents: &[u8]) -> std::result::Result<(), host::Error> {
+    std::fs::write(path.trim(), contents)
+        .map_err(|e| host::Error::io(format!("Could not write {path}"), e))
+}
+
+/// Signs `claims` with the secret key at `key_path` and writes the proof of
+/// delivery, or the carrier's delivery receipt, to `token_path`.
+fn sign_claims(
+    claims: &CustomClaims,
+    key_path: &str,
+    token_path: &str,
+    is_delivery_receipt: bool,
+) -> std::result::Result<(), host::Error> {
+    let issuer = read_file(key_path)?.parse::<Issuer>()?;
+    let token = if is_delivery_receipt {
+        issuer.sign_delivery_receipt(claims)?
+    } else {
+        issuer.generate_token(claims)?
+    };
+    write_file(token_path, token.as_bytes())
+}
+
+/// Lines scrolled by <PgUp> and <PgDn>.
+const PAGE: u16 = 10;
+
+/// The scroll offset after pressing `code`, if it is a scrolling key.
+fn scrolled(scroll: u16, code: KeyCode) -> Option<u16> {
+    match code {
+        KeyCode::Up => Some(scroll.saturating_sub(1)),
+        KeyCode::Down => Some(scroll.saturating_add(1)),
+        KeyCode::PageUp => Some(scroll.saturating_sub(PAGE)),
+        KeyCode::PageDown => Some(scroll.saturating_add(PAGE)),
+        KeyCode::Home => Some(0),
+        _ => None,
+    }
+}
+
+fn percentage_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+struct SelectScreen {
+    list_state: ListState,
+}
+
+impl Default for SelectScreen {
+    fn default() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        SelectScreen { list_state }
+    }
+}
+
+impl SelectScreen {
+    /// Render the UI with various lists.
+    fn render(&mut self, frame: &mut Frame) {
+        let constraints = [
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ];
+        let layout = Layout::vertical(constraints).spacing(1);
+        let [top, first, _second] = frame.area().inner(Margin::new(2, 2)).layout(&layout);
+
+        let title = Line::from_iter([
+            Span::from("Zero-Knowledge Proof of Delivery").bold(),
+            Span::from(" (Press 'q' to quit and arrow keys to navigate)"),
+        ]);
+        frame.render_widget(title.centered(), top);
+
+        self.render_list(frame, first);
+    }
+
+    pub fn render_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items = [
+            "Generate and sign Proof of Delivery",
+            "Edit claims and sign Proof of Delivery",
+            "Generate a Zero-Knowledge Proof of Delivery",
+            "Verify a Zero-Knowledge Proof of Delivery",
+        ];
+        let list = List::new(items)
+            .style(Color::White)
+            .highlight_style(Modifier::REVERSED)
+            .highlight_symbol("> ");
+
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn on_key_press(&mut self, event: KeyEvent) -> Option<usize> {
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.list_state.select_next();
+                None
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.list_state.select_previous();
+                None
+            }
+            KeyCode::Char('q') | KeyCode::Esc => Some(410),
+            KeyCode::Enter => self.list_state.selected(),
+            _ => None,
+        }
+    }
+}
+
+struct InputForm {
+    focus: usize,
+    fields: Vec<StringField>,
+}
+
+impl InputForm {
+    // Handle focus navigation or pass the event to the focused field.
+    fn on_key_press(&mut self, event: KeyEvent) {
+        match event.code {
+            KeyCode::Tab | KeyCode::Down => {
+                if self.focus < self.fields.len() - 1 {
+                    self.focus += 1;
+                }
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                if self.focus > 0 {
+                    self.focus -= 1;
+                }
+            }
+            _ => self.fields[self.focus].on_key_press(event),
+        }
+    }
+
+    /// Renders the form and returns the area left below its fields.
+    fn render(&self, frame: &mut Frame) -> Rect {
+        let area = frame.area();
+
+        let block =
+            Block::bordered().title("Press <Enter> to submit, <Ctrl+O> to browse for a path");
+        let area = percentage_area(area, 80, 80);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let mut constraints = vec![Constraint::Length(1); self.fields.len()];
+        constraints.push(Constraint::Fill(1));
+        let layout = Layout::vertical(constraints);
+        let areas = area.inner(Margin::new(2, 2)).layout_vec(&layout);
+        for (field, area) in self.fields.iter().zip(areas.iter()) {
+            frame.render_widget(field, *area);
+        }
+
+        frame.set_cursor_position(self.fields[self.focus].cursor_position(areas[self.focus]));
+        areas[self.fields.len()]
+    }
+
+    /// The first field whose value cannot be used, if any.
+    fn first_invalid_field(&self) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|field| field.problem().is_some())
+    }
+
+    pub fn get_form_fields(&self) -> Vec<String> {
+        self.fields.iter().map(|e| e.value.clone()).collect()
+    }
+
+    /// Sets the fields to values saved by an earlier session, unless the
+    /// form has changed since.
+    fn restore(&mut self, values: Vec<String>) {
+        if values.len() == self.fields.len() {
+            for (field, value) in self.fields.iter_mut().zip(values) {
+                field.set_value(value);
+            }
+        }
+    }
+}
+
+/// Index of the prove form field holding the path of keys to add.
+const KEY_PATH_FIELD: usize = 4;
+
+/// A verification key in the prove form's key list.
+struct KeyEntry {
+    label: String,
+    /// The JWK, or why it could not be read.
+    jwk: std::result::Result<String, String>,
+    fingerprint: Option<String>,
+}
+
+impl KeyEntry {
+    fn new(key: LoadedKey) -> Self {
+        let fingerprint = key
+            .jwk
+            .parse::<Validator>()
+            .ok()
+            .and_then(|_| compute_fingerprint(&key.jwk).ok());
+        KeyEntry {
+            label: key.label,
+            jwk: Ok(key.jwk),
+            fingerprint,
+        }
+    }
+}
+
+/// The verification keys of the prove form, which can be added from JWK and
+/// JWKS files or directories and removed again.
+#[derive(Default)]
+struct KeyList {
+    /// Name of the config's key set the list started from, if any.
+    set: String,
+    entries: Vec<KeyEntry>,
+    list_state: ListState,
+}
+
+impl KeyList {
+    /// Loads the keys at `paths`, keeping an entry that shows the problem for
+    /// any path that cannot be read.
+    fn from_paths<S: AsRef<str>>(paths: &[S]) -> Self {
+        let mut list = KeyList::default();
+        for path in paths.iter().map(AsRef::as_ref) {
+            if let Err(e) = list.add(path) {
+                list.entries.push(KeyEntry {
+                    label: path.to_string(),
+                    jwk: Err(e.to_string()),
+                    fingerprint: None,
+                });
+            }
+        }
+        list
+    }
+
+    /// Loads the key set of `config` named `name`.
+    fn from_set(config: &Config, name: &str) -> Self {
+        KeyList {
+            set: name.to_string(),
+            ..KeyList::from_paths(config.key_set_paths(name))
+        }
+    }
+
+    fn add(&mut self, path: &str) -> std::result::Result<(), host::Error> {
+        self.extend(load_keys(Path::new(path))?);
+        Ok(())
+    }
+
+    fn extend(&mut self, keys: Vec<LoadedKey>) {
+        self.entries.extend(keys.into_iter().map(KeyEntry::new));
+        if self.list_state.selected().is_none() && !self.entries.is_empty() {
+            self.list_state.select_first();
+        }
+    }
+
+    fn remove_selected(&mut self) {
+        if let Some(index) = self.list_state.selected() {
+            if index < self.entries.len() {
+                self.entries.remove(index);
+            }
+            if self.entries.is_empty() {
+                self.list_state.select(None);
+            } else if index >= self.entries.len() {
+                self.list_state.select(Some(self.entries.len() - 1));
+            }
+        }
+    }
+
+    /// The keys that could be read, with their labels.
+    fn loaded_keys(&self) -> Vec<LoadedKey> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                Some(LoadedKey {
+                    label: entry.label.clone(),
+                    jwk: entry.jwk.clone().ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// The keys to prove with, which fails while any entry could not be
+    /// read.
+    fn jwks(&self) -> std::result::Result<Vec<String>, host::Error> {
+        if self.entries.is_empty() {
+            return Err(host::Error::BadInput(
+                "Add at least one verification key".to_string(),
+            ));
+        }
+        self.entries
+            .iter()
+            .map(|entry| {
+                entry.jwk.clone().map_err(|e| {
+                    host::Error::BadInput(format!("{}: {e} (remove it with <Ctrl+X>)", entry.label))
+                })
+            })
+            .collect()
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let set = if self.set.is_empty() {
+            String::new()
+        } else {
+            format!("from {}, ", self.set)
+        };
+        let title = format!(
+            "Verification keys ({set}{}): <Ctrl+N> add from the path above, <Ctrl+X> remove, <PgUp>/<PgDn> select, <Ctrl+K> next key set, <Ctrl+T> inspect the token",
+            self.entries.len()
+        );
+        let items = self.entries.iter().map(|entry| {
+            let status = match (&entry.jwk, &entry.fingerprint) {
+                (Err(e), _) => Span::from(e.clone()).fg(Color::Red),
+                (Ok(_), Some(fingerprint)) => Span::from(fingerprint.clone()).fg(Color::Green),
+                (Ok(_), None) => Span::from("not a usable verification key").fg(Color::Red),
+            };
+            Line::from_iter([Span::from(format!("{}  ", entry.label)).bold(), status])
+        });
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Modifier::REVERSED)
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+}
+
+/// Indices of the claims editor form's fields.
+const CLAIM_KEY_FIELD: usize = 0;
+const CLAIM_VALUE_FIELD: usize = 1;
+const CLAIMS_FILE_FIELD: usize = 2;
+const SIGNING_KEY_FIELD: usize = 3;
+const TOKEN_FIELD: usize = 4;
+
+/// Free-form editing of the claims to sign, in the claims file format that
+/// `zkpod sign` reads.  Line items loaded from a file are kept as they are.
+struct ClaimsEditor {
+    form: InputForm,
+    claims: CustomClaims,
+    list_state: ListState,
+    /// Name of the config's claim template the claims started from, if any.
+    template: Option<String>,
+}
+
+impl ClaimsEditor {
+    fn new(claims: CustomClaims, paths: &DefaultPaths) -> Self {
+        let form = InputForm {
+            fields: Vec::from([
+                StringField::new("Claim key", "".to_owned()),
+                StringField::new("Claim value", "".to_owned()),
+                StringField::new("Claims file", paths.claims_file.clone())
+                    .with_path(PathKind::Output, &["json"]),
+                StringField::new("Path to signing key", paths.signing_key.clone())
+                    .with_path(PathKind::Input, &["jwk"]),
+                StringField::new("Path to output proof of delivery", paths.token.clone())
+                    .with_path(PathKind::Output, &["jwt"]),
+            ]),
+            focus: 0,
+        };
+        let mut editor = ClaimsEditor {
+            form,
+            claims: CustomClaims::new(),
+            list_state: ListState::default(),
+            template: None,
+        };
+        editor.replace(claims);
+        editor
+    }
+
+    fn replace(&mut self, claims: CustomClaims) {
+        self.claims = claims;
+        self.list_state
+            .select((!self.claims.items().is_empty()).then_some(0));
+    }
+
+    /// Adds the claim in the key and value fields, or updates the value of
+    /// the claim with that key.  New claims are public.
+    fn set_claim(&mut self) -> std::result::Result<(), host::Error> {
+        let key = self.form.fields[CLAIM_KEY_FIELD].value.trim().to_string();
+        if key.is_empty() {
+            return Err(host::Error::BadInput("A claim needs a key".to_string()));
+        }
+        if let Some(problem) = self.form.fields[CLAIM_VALUE_FIELD].problem() {
+            return Err(host::Error::BadInput(format!("{key}: {problem}")));
+        }
+        let value = self.form.fields[CLAIM_VALUE_FIELD].value.clone();
+
+        let items = self.claims.items_mut();
+        let index = match items.iter().position(|item| item.key == key) {
+            Some(index) => {
+                items[index].value = value;
+                index
+            }
+            None => {
+                items.push(ClaimItem {
+                    key,
+                    value,
+                    is_private: false,
+                });
+                items.len() - 1
+            }
+        };
+        self.list_state.select(Some(index));
+        self.form.fields[CLAIM_KEY_FIELD].set_value(String::new());
+        self.form.fields[CLAIM_VALUE_FIELD].set_value(String::new());
+        self.sync_value_check();
+        Ok(())
+    }
+
+    /// Checks the value field as the claim named in the key field needs.
+    fn sync_value_check(&mut self) {
+        let check = Check::for_claim(&self.form.fields[CLAIM_KEY_FIELD].value);
+        self.form.fields[CLAIM_VALUE_FIELD].check = check;
+    }
+
+    fn selected(&self) -> Option<usize> {
+        self.list_state
+            .selected()
+            .filter(|&index| index < self.claims.items().len())
+    }
+
+    /// Copies the selected claim into the key and value fields.
+    fn edit_selected(&mut self) {
+        if let Some(index) = self.selected() {
+            let item = &self.claims.items()[index];
+            self.form.fields[CLAIM_KEY_FIELD].set_value(item.key.clone());
+            self.form.fields[CLAIM_VALUE_FIELD].set_value(item.value.clone());
+            self.form.focus = CLAIM_VALUE_FIELD;
+            self.sync_value_check();
+        }
+    }
+
+    fn remove_selected(&mut self) {
+        if let Some(index) = self.selected() {
+            let items = self.claims.items_mut();
+            items.remove(index);
+            let last = items.len().checked_sub(1);
+            self.list_state.select(last.map(|last| index.min(last)));
+        }
+    }
+
+    fn toggle_private(&mut self) {
+        if let Some(index) = self.selected() {
+            let item = &mut self.claims.items_mut()[index];
+            item.is_private = !item.is_private;
+        }
+    }
+
+    /// Moves the selected claim one place up or down.
+    fn move_selected(&mut self, up: bool) {
+        if let Some(index) = self.selected() {
+            let other = if up {
+                index.checked_sub(1)
+            } else {
+                Some(index + 1).filter(|&other| other < self.claims.items().len())
+            };
+            if let Some(other) = other {
+                self.claims.items_mut().swap(index, other);
+                self.list_state.select(Some(other));
+            }
+        }
+    }
+
+    fn load(&mut self, path: &Path) -> std::result::Result<(), host::Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| host::Error::io(format!("Could not read {}", path.display()), e))?;
+        self.replace(serde_json::from_str(&contents)?);
+        Ok(())
+    }
+
+    fn save(&self, path: &Path) -> std::result::Result<(), host::Error> {
+        let contents = serde_json::to_string_pretty(&self.claims)?;
+        std::fs::write(path, contents)
+            .map_err(|e| host::Error::io(format!("Could not write {}", path.display()), e))
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let rest = self.form.render(frame);
+        let layout = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]);
+        let [list_area, preview_area] = rest.layout(&layout);
+
+        let items = self.claims.items().iter().map(|item| {
+            let visibility = if item.is_private {
+                Span::from("private  ").fg(Color::Red)
+            } else {
+                Span::from("public   ").fg(Color::Green)
+            };
+            Line::from_iter([
+                visibility,
+                Span::from(format!("{}: ", item.key)).bold(),
+                Span::from(item.value.clone()),
+            ])
+        });
+        let template = match &self.template {
+            Some(name) => format!(" (from {name})"),
+            None => String::new(),
+        };
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(
+                "Claims{template}: <Ctrl+N> add or update, <Ctrl+E> edit, <Ctrl+X> remove, \
+                 <Ctrl+P> toggle private, <Ctrl+U>/<Ctrl+D> move, <PgUp>/<PgDn> select"
+            )))
+            .highlight_style(Modifier::REVERSED)
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let private_keys: Vec<&str> = self
+            .claims
+            .items()
+            .iter()
+            .filter(|item| item.is_private)
+            .map(|item| item.key.as_str())
+            .collect();
+        let mut preview = Text::from(Line::from("Revealed by the proof:").bold());
+        preview.extend(Text::from(self.claims.get_public_claims().pretty_print()));
+        preview.push_line(Line::from("Hidden by the proof:").bold());
+        preview.push_line(if private_keys.is_empty() {
+            "nothing".to_string()
+        } else {
+            private_keys.join(", ")
+        });
+        let paragraph = Paragraph::new(preview)
+            .block(Block::bordered().title(
+                "Preview: <Ctrl+L> load, <Ctrl+S> save the claims file, \
+                 <Ctrl+T> take the claims of the generate form, <Ctrl+G> next template",
+            ))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, preview_area);
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::fs::File;
+    use std::io::prelude::*;
+
+    #[test]
+    fn test_default_files_readable() {
+        // Ensures new commits do not mess up test data
+        // Testing takes place in <root>/host so need to go one level up
+        let app = App::default();
+
+        let args = app.gen_and_sign_form.get_form_fields();
+        assert!(File::open(format!("../{}", args[4])).is_ok());
+
+        for path in DEFAULT_VERIFICATION_KEYS {
+            assert!(File::open(format!("../{}", path)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_signing_key_parsable() {
+        // Ensures new commits do not mess up test data
+        // Testing takes place in <root>/host so need to go one level up
+        let app = App::default();
+        let args = app.gen_and_sign_form.get_form_fields();
+
+        // Signing key
+        let mut f = File::open(format!("../{}", args[4])).unwrap();
+        let mut secret_key = "".to_string();
+        f.read_to_string(&mut secret_key).unwrap();
+
+        assert!(secret_key.parse::<Issuer>().is_ok());
+    }
+
+    #[test]
+    fn test_verification_key_parsable() {
+        // Ensures new commits do not mess up test data
+        // Testing takes place in <root>/host so need to go one level up
+        let paths = DEFAULT_VERIFICATION_KEYS.map(|path| format!("../{}", path));
+        let mut keys = KeyList::from_paths(&paths.each_ref().map(String::as_str));
+
+        // Verification keys
+        assert_eq!(keys.jwks().unwrap().len(), 3);
+        assert!(keys.entries.iter().all(|entry| entry.fingerprint.is_some()));
+
+        keys.remove_selected();
+        assert_eq!(keys.jwks().unwrap().len(), 2);
+
+        let mut missing = KeyList::from_paths(&["../test_data/missing.jwk"]);
+        assert!(missing.jwks().is_err());
+        missing.remove_selected();
+        assert!(missing.jwks().is_err());
+    }
+
+    #[test]
+    fn test_failed_submit_keeps_the_form() {
+        let mut app = App {
+            window: AppWindow::Verify,
+            ..App::default()
+        };
+        app.verify_form.fields[0].value = "../test_data/missing.bin".to_string();
+
+        let error = app.submit().unwrap_err();
+        app.show_error(&error);
+        assert!(app.window == AppWindow::Verify);
+        assert!(app.popup.as_ref().is_some_and(|popup| popup.is_error));
+        assert_eq!(app.verify_form.fields[0].value, "../test_data/missing.bin");
+
+        app.window = AppWindow::Prove;
+        app.prove_form.fields[0].value = "../test_data/missing.jwt".to_string();
+        assert!(matches!(app.submit(), Err(host::Error::Io { .. })));
+        assert!(app.window == AppWindow::Prove);
+    }
+
+    #[test]
+    fn test_config_and_session() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "paths": {"receipt": "./project.bin"},
+                "key_sets": {"none": [], "missing": ["../test_data/missing.jwk"]},
+                "key_set": "none",
+                "claim_templates": {"empty": {"claims": []}}
+            }"#,
+        )
+        .unwrap();
+        let mut app = App::new(config);
+        assert_eq!(app.verify_form.fields[0].value, "./project.bin");
+        assert_eq!(app.prove_form.fields[1].value, "./project.bin");
+        assert!(app.prove_keys.entries.is_empty());
+
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        app.on_key_list_key_press(ctrl('k'));
+        assert_eq!(app.prove_keys.set, "missing");
+        assert!(app.prove_keys.jwks().is_err());
+
+        app.on_claims_editor_key_press(ctrl('g'));
+        assert_eq!(app.claims_editor.template.as_deref(), Some("empty"));
+        assert!(app.claims_editor.claims.items().is_empty());
+
+        app.verify_form.fields[0].value = "./other.bin".to_string();
+        let session = serde_json::to_string(&app.session()).unwrap();
+        let mut restored = App::default();
+        restored.restore(serde_json::from_str(&session).unwrap());
+        assert_eq!(restored.verify_form.fields[0].value, "./other.bin");
+        assert_eq!(restored.prove_keys.set, "missing");
+        assert!(restored.claims_editor.claims.items().is_empty());
+
+        restored.restore(serde_json::from_str(r#"{"verify": ["a", "b"]}"#).unwrap());
+        assert_eq!(restored.verify_form.fields[0].value, "./other.bin");
+
+        let path = std::env::temp_dir().join(format!("zkpod-session-{}.json", std::process::id()));
+        std::fs::write(&path, "{not json").unwrap();
+        let mut app = App::default();
+        app.load_session(path.clone());
+        std::fs::remove_file(&path).unwrap();
+        assert!(app.popup.as_ref().is_some_and(|popup| !popup.is_error));
+        assert_eq!(app.session_path, Some(path));
+        assert_eq!(
+            app.verify_form.fields[0].value,
+            App::default().verify_form.fields[0].value
+        );
+    }
+
+    #[test]
+    fn test_inspect_token() {
+        let mut app = App::default();
+        let paths = DEFAULT_VERIFICATION_KEYS.map(|path| format!("../{}", path));
+        app.prove_keys = KeyList::from_paths(&paths.each_ref().map(String::as_str));
+
+        let token_path =
+            std::env::temp_dir().join(format!("zkpod-inspect-{}.jwt", std::process::id()));
+        let token_path = token_path.to_str().unwrap();
+        sign_claims(
+            &app.claims_editor.claims,
+            "../test_data/coffee_company_sk.jwk",
+            token_path,
+            false,
+        )
+        .unwrap();
+        app.prove_form.fields[0].value = token_path.to_string();
+
+        let inspection = app.inspect_token().unwrap();
+        std::fs::remove_file(token_path).unwrap();
+        assert_eq!(inspection.signer, Some(1));
+        assert_eq!(inspection.keys[1].label, "coffee_company_pk.jwk");
+    }
+
+    #[test]
+    fn test_claims_editor() {
+        let app = App::default();
+        let mut editor = app.claims_editor;
+        assert_eq!(editor.claims.get("issuer_id"), Some("Coffee Chain 1"));
+
+        editor.replace(CustomClaims::new());
+        for (key, value) in [("issuer_id", "Coffee Chain 1"), ("batch", "42")] {
+            editor.form.fields[CLAIM_KEY_FIELD].value = key.to_string();
+            editor.form.fields[CLAIM_VALUE_FIELD].value = value.to_string();
+            editor.set_claim().unwrap();
+        }
+        assert!(editor.set_claim().is_err());
+        editor.form.fields[CLAIM_KEY_FIELD].value = "quantity".to_string();
+        editor.form.fields[CLAIM_VALUE_FIELD].value = "ten".to_string();
+        editor.sync_value_check();
+        assert_eq!(editor.form.first_invalid_field(), Some(CLAIM_VALUE_FIELD));
+        assert!(editor.set_claim().is_err());
+        editor.form.fields[CLAIM_KEY_FIELD].set_value(String::new());
+        editor.sync_value_check();
+        assert!(editor.form.fields[CLAIM_VALUE_FIELD].problem().is_none());
+
+        editor.toggle_private();
+        editor.move_selected(true);
+        let keys: Vec<&str> = editor
+            .claims
+            .items()
+            .iter()
+            .map(|c| c.key.as_str())
+            .collect();
+        assert_eq!(keys, ["batch", "issuer_id"]);
+        assert_eq!(editor.claims.get_public_claims().get("batch"), None);
+
+        let path = std::env::temp_dir().join(format!("zkpod-claims-{}.json", std::process::id()));
+        editor.save(&path).unwrap();
+        editor.remove_selected();
+        assert_eq!(editor.claims.items().len(), 1);
+        editor.load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(editor.claims.items().len(), 2);
+        assert!(editor.claims.items()[0].is_private);
+
+        let token_path = path.with_extension("jwt");
+        let token_path = token_path.to_str().unwrap();
+        sign_claims(
+            &editor.claims,
+            "../test_data/coffee_company_sk.jwk",
+            token_path,
+            false,
+        )
+        .unwrap();
+        let token = std::fs::read_to_string(token_path).unwrap();
+        std::fs::remove_file(token_path).unwrap();
+        let pk = std::fs::read_to_string("../test_data/coffee_company_pk.jwk").unwrap();
+        let claims = pk
+            .parse::<Validator>()
+            .unwrap()
+            .validate_token_integrity(&token)
+            .unwrap();
+        assert_eq!(claims.claims().custom.get("batch"), Some("42"));
+    }
+}
@@ -0,0 +1,322 @@
+//! Result screen for verifying a Zero-Knowledge Proof of Delivery.
+
+use crossterm::event::KeyEvent;
+use host::report::VerificationReport;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+use serde_json::Value;
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
+
+use crate::file_browser::PathKind;
+use crate::string_field::StringField;
+use crate::{percentage_area, scrolled, InputForm};
+
+pub struct ReportView {
+    pub report: VerificationReport,
+    receipt_path: String,
+    /// Label of each key the TUI knows, by fingerprint.
+    labels: HashMap<String, String>,
+    scroll: u16,
+    pub form: InputForm,
+}
+
+impl ReportView {
+    pub fn new(
+        report: VerificationReport,
+        receipt_path: String,
+        labels: HashMap<String, String>,
+        save_path: &str,
+    ) -> Self {
+        ReportView {
+            report,
+            receipt_path,
+            labels,
+            scroll: 0,
+            form: InputForm {
+                fields: Vec::from([StringField::new(
+                    "Save the JSON report to",
+                    save_path.to_owned(),
+                )
+                .with_path(PathKind::Output, &["json"])]),
+                focus: 0,
+            },
+        }
+    }
+
+    /// Whether the receipt proves nothing: a fake receipt from dev mode, or
+    /// a verifier that accepts them.
+    fn is_dev_mode(&self) -> bool {
+        self.report.receipt_kind.as_deref() == Some("fake")
+            || std::env::var("RISC0_DEV_MODE").is_ok_and(|value| !value.is_empty() && value != "0")
+    }
+
+    /// Scrolls the report, returning whether the key was a scrolling key.
+    pub fn on_key_press(&mut self, event: KeyEvent) -> bool {
+        match scrolled(self.scroll, event.code) {
+            Some(scroll) => {
+                self.scroll = scroll;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn save_path(&self) -> String {
+        self.form.fields[0].value.clone()
+    }
+
+    fn details(&self) -> Text<'static> {
+        let report = &self.report;
+        let mut text = Text::default();
+
+        section(&mut text, "Receipt");
+        text.push_line(row("Path", &self.receipt_path, 16));
+        text.push_line(row("Image ID", &report.image_id, 16));
+        text.push_line(row(
+            "Kind",
+            report.receipt_kind.as_deref().unwrap_or("unknown"),
+            16,
+        ));
+        if let Some(size) = report.receipt_size {
+            text.push_line(row("Size", &format!("{size} bytes"), 16));
+        }
+
+        if !report.is_verified() {
+            section(&mut text, "Why verification failed");
+            for reason in report.failure_reasons.iter() {
+                text.push_line(Line::from(format!("  {reason}")).fg(Color::Red));
+            }
+            return text;
+        }
+
+        section(
+            &mut text,
+            &format!(
+                "Signed by one of {} distinct candidate keys",
+                report.distinct_keys
+            ),
+        );
+        let labels: Vec<&str> = report
+            .key_fingerprints
+            .iter()
+            .map(|fingerprint| {
+                self.labels
+                    .get(fingerprint)
+                    .map_or("(unknown key)", String::as_str)
+            })
+            .collect();
+        let width = labels.iter().map(|label| label.width()).max().unwrap_or(0);
+        text.push_line(
+            Line::from(format!(
+                "  {:width$}  {:43}  Validity",
+                "Label", "Fingerprint"
+            ))
+            .bold(),
+        );
+        for ((label, fingerprint), validity) in labels
+            .iter()
+            .zip(report.key_fingerprints.iter())
+            .zip(report.key_validity.iter())
+        {
+            let validity = if validity.is_unbounded() {
+                "any time".to_string()
+            } else {
+                validity.to_string()
+            };
+            text.push_line(format!(
+                "  {}  {fingerprint:43}  {validity}",
+                pad(label, width)
+            ));
+        }
+        if !report.key_validity_checked {
+            text.push_line("  Validity windows are as asserted by the prover, not checked against the issuer's key set");
+        }
+
+        section(&mut text, "Disclosed claims");
+        let width = report
+            .public_claims
+            .keys()
+            .map(|key| key.width())
+            .max()
+            .unwrap_or(0);
+        for (key, value) in report.public_claims.iter() {
+            text.push_line(row(key, &claim_value(value), width));
+        }
+        text.push_line(
+            Line::from(format!(
+                "  {} claims are hidden by the proof",
+                report.hidden_claims
+            ))
+            .italic(),
+        );
+        if report.hidden_line_item_fields > 0 {
+            text.push_line(
+                Line::from(format!(
+                    "  {} line item fields are hidden by the proof",
+                    report.hidden_line_item_fields
+                ))
+                .italic(),
+            );
+        }
+
+        if !report.claim_ranges.is_empty()
+            || !report.unit_price_bands.is_empty()
+            || !report.date_windows.is_empty()
+        {
+            section(&mut text, "Proven statements");
+            for range in report.claim_ranges.iter() {
+                let bounds = match range.max {
+                    Some(max) => format!("{} to {}", range.min, max),
+                    None => format!("{} or more", range.min),
+                };
+                text.push_line(format!("  {} is {bounds} ({})", range.claim, range.label));
+            }
+            for band in report.unit_price_bands.iter() {
+                text.push_line(format!(
+                    "  {} / {} is {} to {}",
+                    band.cost_claim, band.quantity_claim, band.min, band.max
+                ));
+            }
+            for window in report.date_windows.iter() {
+                text.push_line(format!(
+                    "  {} is {} to {}",
+                    window.claim, window.from, window.until
+                ));
+            }
+        }
+
+        if let Some(delivery) = &report.delivery {
+            section(&mut text, "Delivery receipt");
+            for fingerprint in delivery.key_fingerprints.iter() {
+                text.push_line(row("Carrier key", fingerprint, 16));
+            }
+            for (key, value) in delivery.public_claims.iter() {
+                text.push_line(row(key, &claim_value(value), 16));
+            }
+        }
+        if let Some(audit) = &report.audit {
+            section(&mut text, "Audit");
+            text.push_line(row("Auditor key", &audit.key_fingerprint, 16));
+        }
+        if !report.revocation.is_empty() {
+            section(&mut text, "Not revoked by");
+            for revocation in report.revocation.iter() {
+                let checked = if revocation.checked {
+                    ""
+                } else {
+                    ", not checked against the current list"
+                };
+                text.push_line(format!(
+                    "  {}: version {} (root {}){checked}",
+                    revocation.key_fingerprint, revocation.version, revocation.root
+                ));
+            }
+        }
+        text
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let area = percentage_area(frame.area(), 90, 90);
+        frame.render_widget(Clear, area);
+
+        let warning_height = if self.is_dev_mode() { 1 } else { 0 };
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(warning_height),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ]);
+        let [banner_area, warning_area, details_area, save_area] = area.layout(&layout);
+
+        let (banner, color) = if self.report.is_verified() {
+            ("VERIFIED", Color::Green)
+        } else {
+            ("VERIFICATION FAILED", Color::Red)
+        };
+        let banner = Paragraph::new(Line::from(banner).bold().centered())
+            .block(Block::bordered())
+            .fg(color);
+        frame.render_widget(banner, banner_area);
+
+        if self.is_dev_mode() {
+            let warning =
+                Line::from("Dev mode: fake receipts are accepted, so this result proves nothing")
+                    .fg(Color::Yellow)
+                    .bold()
+                    .centered();
+            frame.render_widget(warning, warning_area);
+        }
+
+        let details = Paragraph::new(self.details())
+            .block(Block::bordered().title("Report: <Up>/<Down>/<PgUp>/<PgDn> scroll, <Esc> back"))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(details, details_area);
+
+        let block = Block::bordered().title("<Enter> save, <Ctrl+O> browse");
+        let inner = block.inner(save_area);
+        frame.render_widget(block, save_area);
+        frame.render_widget(&self.form.fields[0], inner);
+        frame.set_cursor_position(self.form.fields[0].cursor_position(inner));
+    }
+}
+
+fn section(text: &mut Text<'static>, title: &str) {
+    if !text.lines.is_empty() {
+        text.push_line("");
+    }
+    text.push_line(Line::from(title.to_string()).bold().underlined());
+}
+
+fn row(key: &str, value: &str, width: usize) -> Line<'static> {
+    Line::from_iter([
+        Span::from(format!("  {}  ", pad(key, width))).bold(),
+        Span::from(value.to_string()),
+    ])
+}
+
+/// Pads `text` with spaces to `width` terminal columns; `format!` pads by
+/// characters, which misaligns wide characters.
+fn pad(text: &str, width: usize) -> String {
+    format!("{text}{}", " ".repeat(width.saturating_sub(text.width())))
+}
+
+fn claim_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_failed_report_details() {
+        let report = VerificationReport::from_receipt_bytes(b"not a receipt");
+        let view = ReportView::new(
+            report,
+            "zkpod.bin".to_string(),
+            HashMap::new(),
+            "./zkpod_report.json",
+        );
+        let details = view.details().to_string();
+        assert!(details.contains("13 bytes"));
+        assert!(details.contains("Why verification failed"));
+        assert!(details.contains("Could not deserialise bytes as receipt"));
+    }
+
+    #[test]
+    fn test_rows_align_wide_characters() {
+        let width = ["数量", "qty"].iter().map(|key| key.width()).max().unwrap();
+        assert_eq!(
+            row("数量", "1", width).width(),
+            row("qty", "2", width).width()
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Single line text field of the forms, with a cursor, word-wise editing and
+//! checks of its value shown next to it.
+
+use chrono::{Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use host::invoice::parse_line_items;
+use jwt_core::statement::DELIVERY_DATE_CLAIM;
+use jwt_core::{BucketDefinition, DateWindow, LineItemDisclosure, Money};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::file_browser::{PathKind, PathSpec};
+
+/// What the value of a field must be for the form to be submitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// A whole number, such as a quantity.
+    Quantity,
+    /// An amount and its currency, such as `4000.00 GBP`.
+    Money,
+    /// A date as `YYYY-MM-DD`.
+    Date,
+    /// Line items separated by `;`.
+    LineItems,
+    /// Line item fields to disclose, separated by `,`.
+    Disclosure,
+    /// Bucket definitions separated by `;`, or nothing.
+    Buckets,
+    /// A trailing period such as `12m`, or nothing.
+    Period,
+    /// One of the given values.
+    OneOf(&'static [&'static str]),
+}
+
+impl Check {
+    /// The check for the value of a claim named `key`, if it has one.
+    pub fn for_claim(key: &str) -> Option<Self> {
+        match key.trim() {
+            "quantity" | "total_quantity" => Some(Check::Quantity),
+            "cost" => Some(Check::Money),
+            DELIVERY_DATE_CLAIM => Some(Check::Date),
+            _ => None,
+        }
+    }
+
+    /// Why `value` does not pass the check, if it does not.
+    pub fn problem(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        match self {
+            Check::Quantity => value
+                .parse::<u64>()
+                .err()
+                .map(|_| "not a whole number".to_string()),
+            Check::Money => value.parse::<Money>().err().map(|e| e.to_string()),
+            Check::Date => value
+                .parse::<NaiveDate>()
+                .err()
+                .map(|_| "expected YYYY-MM-DD".to_string()),
+            Check::LineItems => match parse_line_items(value) {
+                Ok(line_items) if line_items.is_empty() => Some("no line items".to_string()),
+                Ok(_) => None,
+                Err(e) => Some(e.to_string()),
+            },
+            Check::Disclosure => value
+                .parse::<LineItemDisclosure>()
+                .err()
+                .map(|e| e.to_string()),
+            Check::Buckets => value
+                .split(';')
+                .map(str::trim)
+                .filter(|bucket| !bucket.is_empty())
+                .find_map(|bucket| bucket.parse::<BucketDefinition>().err())
+                .map(|e| e.to_string()),
+            Check::Period if value.is_empty() => None,
+            Check::Period => {
+                DateWindow::trailing(DELIVERY_DATE_CLAIM, value, Local::now().date_naive())
+                    .err()
+                    .map(|e| e.to_string())
+            }
+            Check::OneOf(values) if values.contains(&value) => None,
+            Check::OneOf(values) => Some(format!("expected {}", values.join(" or "))),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StringField {
+    pub label: &'static str,
+    pub value: String,
+    /// Byte offset of the cursor in `value`.
+    cursor: usize,
+    /// Set for fields holding a path, which can be browsed for.
+    pub path: Option<PathSpec>,
+    pub check: Option<Check>,
+}
+
+impl StringField {
+    pub fn new(label: &'static str, value: String) -> Self {
+        Self {
+            label,
+            cursor: value.len(),
+            value,
+            path: None,
+            check: None,
+        }
+    }
+
+    pub fn with_path(self, kind: PathKind, extensions: &'static [&'static str]) -> Self {
+        Self {
+            path: Some(PathSpec { kind, extensions }),
+            ..self
+        }
+    }
+
+    pub fn with_check(self, check: Check) -> Self {
+        Self {
+            check: Some(check),
+            ..self
+        }
+    }
+
+    /// Replaces the value, with the cursor at its end.
+    pub fn set_value(&mut self, value: String) {
+        self.cursor = value.len();
+        self.value = value;
+    }
+
+    /// Why the value cannot be used, if it cannot.
+    pub fn problem(&self) -> Option<String> {
+        self.path
+            .and_then(|spec| spec.problem(&self.value))
+            .map(str::to_string)
+            .or_else(|| self.check.and_then(|check| check.problem(&self.value)))
+    }
+
+    /// The cursor, kept on a character boundary of the value even if the
+    /// value was assigned directly.
+    fn cursor(&self) -> usize {
+        let mut cursor = self.cursor.min(self.value.len());
+        while !self.value.is_char_boundary(cursor) {
+            cursor -= 1;
+        }
+        cursor
+    }
+
+    fn previous_char(&self, cursor: usize) -> usize {
+        self.value[..cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn next_char(&self, cursor: usize) -> usize {
+        self.value[cursor..]
+            .chars()
+            .next()
+            .map_or(cursor, |c| cursor + c.len_utf8())
+    }
+
+    /// Start of the word before the cursor, words being runs of letters and
+    /// digits.
+    fn previous_word(&self, cursor: usize) -> usize {
+        let before = &self.value[..cursor];
+        let end = before
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .len();
+        before[..end].trim_end_matches(char::is_alphanumeric).len()
+    }
+
+    /// End of the word after the cursor.
+    fn next_word(&self, cursor: usize) -> usize {
+        let after = &self.value[cursor..];
+        let start = after.len()
+            - after
+                .trim_start_matches(|c: char| !c.is_alphanumeric())
+                .len();
+        let rest = after[start..].trim_start_matches(char::is_alphanumeric);
+        self.value.len() - rest.len()
+    }
+
+    fn insert(&mut self, text: &str) {
+        let cursor = self.cursor();
+        self.value.insert_str(cursor, text);
+        self.cursor = cursor + text.len();
+    }
+
+    /// Inserts pasted text at the cursor, as a single line.
+    pub fn paste(&mut self, text: &str) {
+        let text: String = text
+            .trim_end_matches(['\r', '\n'])
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        self.insert(&text);
+    }
+
+    pub fn on_key_press(&mut self, event: KeyEvent) {
+        let cursor = self.cursor();
+        let word = event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match event.code {
+            KeyCode::Char('w') if event.modifiers == KeyModifiers::CONTROL => {
+                let start = self.previous_word(cursor);
+                self.value.replace_range(start..cursor, "");
+                self.cursor = start;
+            }
+            KeyCode::Char(c) if !word => self.insert(c.encode_utf8(&mut [0; 4])),
+            KeyCode::Backspace => {
+                let start = if word {
+                    self.previous_word(cursor)
+                } else {
+                    self.previous_char(cursor)
+                };
+                self.value.replace_range(start..cursor, "");
+                self.cursor = start;
+            }
+            KeyCode::Delete => {
+                let end = if word {
+                    self.next_word(cursor)
+                } else {
+                    self.next_char(cursor)
+                };
+                self.value.replace_range(cursor..end, "");
+                self.cursor = cursor;
+            }
+            KeyCode::Left if word => self.cursor = self.previous_word(cursor),
+            KeyCode::Left => self.cursor = self.previous_char(cursor),
+            KeyCode::Right if word => self.cursor = self.next_word(cursor),
+            KeyCode::Right => self.cursor = self.next_char(cursor),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            _ => {}
+        }
+    }
+
+    fn label_width(&self) -> u16 {
+        self.label.width() as u16 + 2
+    }
+
+    /// Byte offset and column of the first character shown in a value area
+    /// `width` columns wide, scrolled so that the cursor stays in it.
+    fn first_visible(&self, width: u16) -> (usize, usize) {
+        let before = self.value[..self.cursor()].width();
+        let scroll = before.saturating_sub(usize::from(width.saturating_sub(1)));
+        let mut column = 0;
+        for (index, c) in self.value.char_indices() {
+            if column >= scroll {
+                return (index, column);
+            }
+            column += c.width().unwrap_or(0);
+        }
+        (self.value.len(), column)
+    }
+
+    /// Where the cursor is when the field is rendered in `area`.
+    pub fn cursor_position(&self, area: Rect) -> Position {
+        let width = area.width.saturating_sub(self.label_width());
+        let before = self.value[..self.cursor()].width() - self.first_visible(width).1;
+        Position::new(area.x + self.label_width() + before as u16, area.y)
+    }
+}
+
+impl Widget for &StringField {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let layout =
+            Layout::horizontal([Constraint::Length(self.label_width()), Constraint::Fill(1)]);
+        let [label_area, value_area] = area.layout(&layout);
+        let label = Line::from_iter([self.label, ": "]).bold();
+        label.render(label_area, buf);
+
+        let (start, _) = self.first_visible(value_area.width);
+        let mut value = Line::from(self.value[start..].to_string());
+        if let Some(problem) = self.problem() {
+            value.push_span(Span::from(format!("  ({problem})")).fg(Color::Red));
+        }
+        value.render(value_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn press(field: &mut StringField, code: KeyCode, modifiers: KeyModifiers) {
+        field.on_key_press(KeyEvent::new(code, modifiers));
+    }
+
+    #[test]
+    fn test_editing() {
+        let mut field = StringField::new("Cost", "4000.00 GBP".to_string());
+        press(&mut field, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Char('1'), KeyModifiers::NONE);
+        assert_eq!(field.value, "14000.00 GBP");
+
+        press(&mut field, KeyCode::Right, KeyModifiers::CONTROL);
+        press(&mut field, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(field.value, "1400000 GBP");
+        press(&mut field, KeyCode::Backspace, KeyModifiers::ALT);
+        assert_eq!(field.value, "00 GBP");
+
+        press(&mut field, KeyCode::End, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(field.value, "00 ");
+        press(&mut field, KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(field.value, "00 ");
+
+        field.paste("£ and\tmore\n");
+        assert_eq!(field.value, "00 £ and more");
+        press(&mut field, KeyCode::Left, KeyModifiers::ALT);
+        press(&mut field, KeyCode::Left, KeyModifiers::ALT);
+        press(&mut field, KeyCode::Backspace, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(field.value, "00 and more");
+        press(&mut field, KeyCode::Delete, KeyModifiers::CONTROL);
+        assert_eq!(field.value, "00  more");
+    }
+
+    #[test]
+    fn test_cursor_position() {
+        let area = Rect::new(0, 0, 20, 1);
+        let mut field = StringField::new("Cost", "£4".to_string());
+        assert_eq!(field.cursor_position(area), Position::new(8, 0));
+        press(&mut field, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(field.cursor_position(area), Position::new(6, 0));
+
+        // Scrolled by whole characters, so the cursor is one column short of
+        // the edge.
+        field.set_value("咖啡".repeat(10));
+        assert_eq!(field.cursor_position(area), Position::new(18, 0));
+
+        // A value assigned directly keeps the cursor on a character boundary.
+        field.value = "咖".to_string();
+        assert_eq!(field.cursor_position(area), Position::new(8, 0));
+    }
+
+    #[test]
+    fn test_checks() {
+        assert_eq!(Check::Quantity.problem(" 1000 "), None);
+        assert!(Check::Quantity.problem("1.5").is_some());
+        assert_eq!(Check::Money.problem("4000.00 GBP"), None);
+        assert!(Check::Money.problem("4000.001 GBP").is_some());
+        assert!(Check::Date.problem("2026-02-30").is_some());
+        assert!(Check::LineItems.problem("beans:many:kg:4.00 GBP").is_some());
+        assert!(Check::LineItems.problem("").is_some());
+        assert_eq!(Check::Buckets.problem(""), None);
+        assert!(Check::Buckets.problem("cost:2,1").is_some());
+        assert_eq!(Check::Period.problem("12m"), None);
+        assert!(Check::Period.problem("soon").is_some());
+        // Typed into the field one character at a time, so must not panic.
+        assert!(Check::Period.problem("12£").is_some());
+        assert!(Check::Period.problem("£").is_some());
+        assert_eq!(
+            Check::OneOf(&["invoice", "carrier-receipt"]).problem("bill"),
+            Some("expected invoice or carrier-receipt".to_string())
+        );
+        assert_eq!(Check::for_claim("cost"), Some(Check::Money));
+
+        let field = StringField::new("Quantity", "ten".to_string()).with_check(Check::Quantity);
+        assert_eq!(field.problem(), Some("not a whole number".to_string()));
+    }
+}
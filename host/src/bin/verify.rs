@@ -16,52 +16,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::Read;
+use clap::Parser;
+use host::cli::{main_with, Command, VerifyArgs};
+use std::process::ExitCode;
 
-use borsh::de::BorshDeserialize;
-use host::compute_fingerprint;
-use jwt_core::PublicOutput;
-use methods::VERIFY_TOKEN_WITH_SOME_KEY_ID;
-use risc0_zkvm::Receipt;
-
-fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-
-    let args: Vec<String> = std::env::args().collect();
-
-    if args.len() < 2 {
-        panic!("Usage: verify /path/to/receipt.bin");
-    }
-
-    let mut f = std::fs::File::open(&args[1]).expect("Could not find receipt file");
-    let mut receipt = Vec::new();
-    f.read_to_end(&mut receipt)
-        .expect("Could not parse token from file");
-
-    let receipt =
-        Receipt::try_from_slice(&receipt).expect("Could not deserialise bytes as receipt");
-
-    let res = receipt.verify(VERIFY_TOKEN_WITH_SOME_KEY_ID);
-    if res.is_ok() {
-        println!("Verification succeeded!");
-        let public_outputs: PublicOutput = receipt
-            .journal
-            .decode()
-            .expect("Could not decode receipt journal");
-        println!("\nThe prover has a JWT signed by the secret key corresponding to one of the following public keys: ");
-
-        let pk_digests: Vec<String> = public_outputs
-            .pks
-            .into_iter()
-            .map(|pk| compute_fingerprint(pk))
-            .collect();
-        println!("{:#?}", pk_digests);
-
-        println!("\nThe JWT attests to the following public claims (and 0 or more undisclosed private claims): ");
-        println!("{:}", public_outputs.claims.pretty_print());
-    } else {
-        println!("Verification failed!")
-    }
+fn main() -> ExitCode {
+    main_with(Command::Verify(VerifyArgs::parse()))
 }
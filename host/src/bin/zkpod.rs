@@ -0,0 +1,7 @@
+use clap::Parser;
+use host::cli::{main_with, Cli};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    main_with(Cli::parse().command)
+}
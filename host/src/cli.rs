@@ -0,0 +1,1003 @@
+//! Command line interface shared by the `zkpod` binary and the standalone
+//! per-command binaries.
+
+use crate::audit::{decrypt_receipt, generate_auditor_key};
+use crate::batch::{run_batch, BatchConfig, BatchManifest, BatchStatus, MANIFEST_FILE_NAME};
+use crate::bench::run_cycle_bench;
+use crate::inspect::TokenInspection;
+use crate::invoice::{add_delivery_date, CarrierReceipt, Invoice, DEFAULT_DISCLOSURE};
+use crate::key_set::{apply_rotations, load_keys, rotate, with_validity};
+use crate::report::VerificationReport;
+use crate::revocation::{parse_revoked_entry, PublishedRevocationList};
+use crate::{
+    deserialize_receipt, prove_token_validation_with, serialize_receipt, Error, ProofOptions,
+};
+use chrono::{Local, NaiveDate};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use jwt_core::revocation::hash_to_hex;
+use jwt_core::statement::DELIVERY_DATE_CLAIM;
+use jwt_core::{
+    jwk_thumbprint, jwk_thumbprint_uri, token_hash, BucketDefinition, CustomClaims, DateWindow,
+    DeliveryReceipt, Issuer, KeyValidity, LineItem, LineItemDisclosure, Money, Statements,
+    ThumbprintHash, UnitPriceBand,
+};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use uuid::Uuid;
+
+/// Path argument that stands for stdin or stdout.
+pub const STDIO: &str = "-";
+
+pub const EXIT_VERIFICATION_FAILED: u8 = 1;
+pub const EXIT_BAD_INPUT: u8 = 2;
+pub const EXIT_IO_ERROR: u8 = 3;
+pub const EXIT_PROVING_FAILED: u8 = 4;
+
+impl Error {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::VerificationFailed(_) => EXIT_VERIFICATION_FAILED,
+            Error::Jwt(_) | Error::Json(_) | Error::BadInput(_) => EXIT_BAD_INPUT,
+            Error::Io { .. } => EXIT_IO_ERROR,
+            Error::ProvingFailed(_) => EXIT_PROVING_FAILED,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "zkpod", version, about = "Zero-Knowledge Proof of Delivery", long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    Claims(ClaimsArgs),
+    Sign(SignArgs),
+    Prove(ProveArgs),
+    Batch(BatchArgs),
+    Verify(VerifyArgs),
+    Fingerprint(FingerprintArgs),
+    Inspect(InspectArgs),
+    Revocation(RevocationArgs),
+    Keys(KeysArgs),
+    AuditKeygen(AuditKeygenArgs),
+    AuditDecrypt(AuditDecryptArgs),
+    Cycles(CyclesArgs),
+    Completions(CompletionsArgs),
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClaimsTemplate {
+    /// Invoice signed by the customer
+    #[default]
+    Invoice,
+    /// Receipt signed by the carrier or warehouse that delivered the goods
+    CarrierReceipt,
+}
+
+/// Generate an invoice or carrier receipt claims file
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct ClaimsArgs {
+    /// Where to write the claims, or '-' for stdout
+    #[arg(long, default_value = "./invoice_claims.json")]
+    pub path_to_claims_file: String,
+
+    /// Kind of document to generate claims for
+    #[arg(long, value_enum, default_value_t = ClaimsTemplate::Invoice)]
+    pub template: ClaimsTemplate,
+
+    /// Reference of the invoice a carrier receipt confirms delivery of
+    #[arg(long, required_if_eq("template", "carrier-receipt"))]
+    pub reference: Option<String>,
+
+    /// Carrier or warehouse that delivered the goods, for a carrier receipt
+    #[arg(long, default_value = "Coffee Haulage")]
+    pub carrier_id: String,
+
+    #[arg(short, long, default_value = "Coffee Chain 1")]
+    pub issuer_id: String,
+
+    #[arg(short, long, default_value = "Coffee Supplier")]
+    pub subject_id: String,
+
+    #[arg(short, long, default_value = "raw coffee beans")]
+    pub product: String,
+
+    #[arg(short, long, default_value = "1000")]
+    pub quantity: u64,
+
+    /// Total cost with its ISO 4217 currency
+    #[arg(short, long, default_value = "4000.00 GBP", value_parser = parse_money)]
+    pub cost: Money,
+
+    /// Invoice line as PRODUCT:QUANTITY:UNIT:UNIT_PRICE; may be repeated, and
+    /// replaces --product, --quantity and --cost
+    #[arg(short, long = "line-item", value_parser = parse_line_item)]
+    pub line_items: Vec<LineItem>,
+
+    /// Comma separated line item fields to disclose: product, quantity, unit,
+    /// unit_price and total_quantity
+    #[arg(long, default_value = DEFAULT_DISCLOSURE, value_parser = parse_disclosure)]
+    pub disclose: LineItemDisclosure,
+
+    /// Date the goods were delivered, as YYYY-MM-DD [default: today]
+    #[arg(long)]
+    pub delivery_date: Option<NaiveDate>,
+}
+
+/// Sign a claims file as a proof of delivery JWT
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct SignArgs {
+    /// Issuer secret key as a JWK
+    pub secret_key: String,
+
+    /// Claims file, or '-' for stdin
+    pub claims: String,
+
+    /// Where to write the token, or '-' for stdout
+    #[arg(default_value = STDIO)]
+    pub token: String,
+
+    /// Sign a carrier's delivery receipt rather than an invoice
+    #[arg(long)]
+    pub delivery_receipt: bool,
+}
+
+/// Prove that a proof of delivery was signed by one of a set of keys
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct ProveArgs {
+    /// Proof of delivery JWT, or '-' for stdin
+    pub token: String,
+
+    /// Where to write the receipt, or '-' for stdout
+    pub receipt: String,
+
+    /// Candidate verification keys as JWKs
+    #[arg(required = true)]
+    pub public_keys: Vec<String>,
+
+    #[command(flatten)]
+    pub statements: StatementArgs,
+
+    /// Carrier's delivery receipt JWT, with the same reference as the token
+    #[arg(long, requires = "carrier_keys")]
+    pub delivery_receipt: Option<String>,
+
+    /// Candidate carrier keys for the delivery receipt; may be repeated
+    #[arg(long = "carrier-key", requires = "delivery_receipt")]
+    pub carrier_keys: Vec<String>,
+}
+
+/// Statements to prove about the private claims of a token
+#[derive(clap::Args, Debug, Default)]
+pub struct StatementArgs {
+    /// Disclose only the range a numeric claim lies in, as CLAIM:B1,B2,...
+    /// with ascending boundaries, e.g. quantity:1000,5000; may be repeated
+    #[arg(long = "bucket", value_parser = parse_bucket)]
+    pub buckets: Vec<BucketDefinition>,
+
+    /// JSON file of bucket definitions, for buckets with custom labels
+    #[arg(long)]
+    pub buckets_file: Option<String>,
+
+    /// Prove that the unit price lies in a band, as COST/QUANTITY=MIN..MAX,
+    /// e.g. 'cost/quantity=3.50 GBP..4.50 GBP'; may be repeated
+    #[arg(long = "unit-price-band", value_parser = parse_unit_price_band)]
+    pub unit_price_bands: Vec<UnitPriceBand>,
+
+    /// Prove that a date claim lies in a window, as CLAIM=FROM..UNTIL, e.g.
+    /// 'delivery_date=2025-04-01..2026-03-31'; may be repeated
+    #[arg(long = "date-window", value_parser = parse_date_window)]
+    pub date_windows: Vec<DateWindow>,
+
+    /// Prove that the delivery date lies in the period ending today, e.g.
+    /// '12m' for the past year; also '30d' or '1y'
+    #[arg(long)]
+    pub delivered_within: Option<String>,
+
+    /// Prove the token is not revoked, given the published revocation list of
+    /// each public key in the same order as the keys
+    #[arg(long = "revocation-list")]
+    pub revocation_lists: Vec<String>,
+
+    /// Encrypt all of the claims to an auditor's RSA public key, given as a
+    /// JWK; the receipt proves the ciphertext holds the signed values
+    #[arg(long)]
+    pub auditor_key: Option<String>,
+}
+
+impl StatementArgs {
+    pub fn to_proof_options(&self) -> Result<ProofOptions, Error> {
+        Ok(ProofOptions {
+            statements: self.to_statements()?,
+            revocation_lists: read_revocation_lists(&self.revocation_lists)?,
+            ..ProofOptions::default()
+        })
+    }
+
+    fn to_statements(&self) -> Result<Statements, Error> {
+        let mut buckets = self.buckets.clone();
+        if let Some(path) = &self.buckets_file {
+            let definitions: Vec<BucketDefinition> = serde_json::from_slice(&read_input(path)?)?;
+            buckets.extend(definitions);
+        }
+        let mut date_windows = self.date_windows.clone();
+        if let Some(period) = &self.delivered_within {
+            date_windows.push(DateWindow::trailing(
+                DELIVERY_DATE_CLAIM,
+                period,
+                Local::now().date_naive(),
+            )?);
+        }
+        Ok(Statements {
+            buckets,
+            unit_price_bands: self.unit_price_bands.clone(),
+            date_windows,
+            auditor_key: self
+                .auditor_key
+                .as_deref()
+                .map(read_input_to_string)
+                .transpose()?,
+            ..Statements::default()
+        })
+    }
+}
+
+/// Prove a directory or manifest of proof of delivery tokens against one key set
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct BatchArgs {
+    /// Directory of .jwt files, or a batch manifest listing token paths
+    pub tokens: PathBuf,
+
+    /// Candidate verification keys as JWKs, shared by every token
+    #[arg(required = true)]
+    pub public_keys: Vec<String>,
+
+    /// Directory to write one receipt per token into
+    #[arg(long, default_value = "./receipts")]
+    pub out_dir: PathBuf,
+
+    /// Manifest recording the outcome of each token; an existing manifest is
+    /// resumed [default: <OUT_DIR>/manifest.json]
+    #[arg(long)]
+    pub manifest: Option<PathBuf>,
+
+    /// Number of tokens to prove at the same time
+    #[arg(short = 'j', long, default_value_t = 1)]
+    pub concurrency: usize,
+
+    #[command(flatten)]
+    pub statements: StatementArgs,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Verify a Zero-Knowledge Proof of Delivery receipt
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct VerifyArgs {
+    /// Receipt file, or '-' for stdin
+    #[arg(default_value = STDIO)]
+    pub receipt: String,
+
+    /// Output format for the verification report
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Current published revocation list of an issuer; the receipt must
+    /// prove the token is not on it; may be repeated
+    #[arg(long = "revocation-list")]
+    pub revocation_lists: Vec<String>,
+
+    /// Trusted public keys of the issuers, as a JWK, a JWKS or a directory
+    /// of JWKs; the candidate keys and their validity windows must match
+    /// them; may be repeated
+    #[arg(long = "key-set")]
+    pub key_set: Vec<String>,
+
+    /// Rotation record signed by one of the trusted keys, applied to their
+    /// validity windows before the check; may be repeated
+    #[arg(long = "rotation", requires = "key_set")]
+    pub rotations: Vec<String>,
+}
+
+/// Print the RFC 7638 thumbprint of a JWK
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct FingerprintArgs {
+    /// Key file, or '-' for stdin
+    #[arg(default_value = STDIO)]
+    pub key: String,
+
+    /// Hash algorithm: sha-256, sha-384 or sha-512
+    #[arg(long, default_value = "sha-256", value_parser = parse_hash)]
+    pub hash: ThumbprintHash,
+
+    /// Print the thumbprint as a urn:ietf:params:oauth:jwk-thumbprint URI
+    #[arg(long)]
+    pub uri: bool,
+}
+
+/// Decode a proof of delivery and check its signature against candidate keys
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct InspectArgs {
+    /// Proof of delivery JWT, or '-' for stdin
+    #[arg(default_value = STDIO)]
+    pub token: String,
+
+    /// Candidate key: a JWK, a JWKS file or a directory of JWK files; may be
+    /// repeated.  Fails unless one of them signed the token
+    #[arg(short = 'k', long = "key")]
+    pub keys: Vec<String>,
+
+    /// Output format for the inspection
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+}
+
+/// Publish revocation lists of proofs of delivery
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct RevocationArgs {
+    #[command(subcommand)]
+    pub command: RevocationCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RevocationCommand {
+    Publish(PublishArgs),
+    Hash(HashArgs),
+}
+
+/// Sign a new version of an issuer's revocation list
+#[derive(Parser, Debug)]
+#[command(long_about = None)]
+pub struct PublishArgs {
+    /// Issuer secret key as a JWK
+    pub secret_key: String,
+
+    /// Revoked tokens, or their hashes, one per line, or '-' for stdin
+    pub revoked: String,
+
+    /// Where to write the published list, or '-' for stdout
+    #[arg(default_value = STDIO)]
+    pub list: String,
+
+    /// Version of the list, which must increase with every publication
+    #[arg(long)]
+    pub list_version: u64,
+}
+
+/// Print the hash under which a token is revoked
+#[derive(Parser, Debug)]
+#[command(long_about = None)]
+pub struct HashArgs {
+    /// Proof of delivery JWT, or '-' for stdin
+    #[arg(default_value = STDIO)]
+    pub token: String,
+}
+
+/// Manage validity windows and rotations of issuer keys
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct KeysArgs {
+    #[command(subcommand)]
+    pub command: KeysCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum KeysCommand {
+    Show(ShowKeysArgs),
+    Window(WindowArgs),
+    Rotate(RotateArgs),
+    Chain(ChainArgs),
+}
+
+/// Print the fingerprint and validity window of keys
+#[derive(Parser, Debug)]
+#[command(long_about = None)]
+pub struct ShowKeysArgs {
+    /// Public keys as JWKs
+    #[arg(required = true)]
+    pub keys: Vec<String>,
+}
+
+/// Set the window in which tokens signed by a key must have been issued
+#[derive(Parser, Debug)]
+#[command(long_about = None)]
+pub struct WindowArgs {
+    /// Key as a JWK, or '-' for stdin
+    pub key: String,
+
+    /// Where to write the key, or '-' for stdout
+    #[arg(default_value = STDIO)]
+    pub output: String,
+
+    /// Start of the window, as seconds since the Unix epoch or RFC 3339
+    #[arg(long, value_parser = parse_timestamp)]
+    pub not_before: Option<u64>,
+
+    /// End of the window (exclusive), as seconds since the Unix epoch or RFC 3339
+    #[arg(long, value_parser = parse_timestamp)]
+    pub not_after: Option<u64>,
+}
+
+/// Generate an RSA key pair for an auditor to receive encrypted claims with
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct AuditKeygenArgs {
+    /// Where to write the auditor's secret key
+    pub secret_key: String,
+
+    /// Where to write the auditor's public key, or '-' for stdout
+    #[arg(default_value = STDIO)]
+    pub public_key: String,
+}
+
+/// Verify a receipt and decrypt the claims it encrypted to the auditor
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct AuditDecryptArgs {
+    /// Receipt file, or '-' for stdin
+    pub receipt: String,
+
+    /// Auditor's secret key as a JWK
+    pub secret_key: String,
+
+    /// Where to write the decrypted claims, or '-' for stdout
+    #[arg(default_value = STDIO)]
+    pub output: String,
+}
+
+/// Count the guest's cycles for key sets of different sizes, before and
+/// after the signing key index was passed to it
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct CyclesArgs {
+    /// Proof of delivery token
+    pub token: String,
+
+    /// Public key that signed the token, as a JWK
+    pub signing_key: String,
+
+    /// Comma separated sizes of the key sets; the other keys are generated
+    #[arg(long, value_delimiter = ',', default_value = "3,30,300")]
+    pub key_counts: Vec<usize>,
+
+    /// Output format for the cycle counts
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// Sign a record handing over from a key to its successor
+#[derive(Parser, Debug)]
+#[command(long_about = None)]
+pub struct RotateArgs {
+    /// Secret key being retired, as a JWK
+    pub previous_secret_key: String,
+
+    /// Public key of the successor, as a JWK
+    pub next_key: String,
+
+    /// Where to write the rotation record, or '-' for stdout
+    #[arg(default_value = STDIO)]
+    pub record: String,
+
+    /// When the successor takes over, as seconds since the Unix epoch or
+    /// RFC 3339; defaults to now
+    #[arg(long, value_parser = parse_timestamp)]
+    pub at: Option<u64>,
+}
+
+/// Derive the validity windows of a key set from its rotation records
+#[derive(Parser, Debug)]
+#[command(long_about = None)]
+pub struct ChainArgs {
+    /// Public keys of the key set, as JWKs
+    #[arg(required = true)]
+    pub keys: Vec<String>,
+
+    /// Rotation record signed by one of the keys; may be repeated
+    #[arg(short, long = "rotation")]
+    pub rotations: Vec<String>,
+
+    /// Directory to write the keys to, under their original file names
+    #[arg(long)]
+    pub out_dir: PathBuf,
+}
+
+/// Generate shell completions for zkpod
+#[derive(Parser, Debug)]
+#[command(version, long_about = None)]
+pub struct CompletionsArgs {
+    pub shell: Shell,
+}
+
+fn parse_hash(s: &str) -> Result<ThumbprintHash, String> {
+    s.parse().map_err(|e: jwt_core::Err| e.to_string())
+}
+
+fn parse_line_item(s: &str) -> Result<LineItem, String> {
+    s.parse().map_err(|e: jwt_core::Err| e.to_string())
+}
+
+fn parse_money(s: &str) -> Result<Money, String> {
+    s.parse().map_err(|e: jwt_core::Err| e.to_string())
+}
+
+fn parse_unit_price_band(s: &str) -> Result<UnitPriceBand, String> {
+    s.parse().map_err(|e: jwt_core::Err| e.to_string())
+}
+
+fn parse_bucket(s: &str) -> Result<BucketDefinition, String> {
+    s.parse().map_err(|e: jwt_core::Err| e.to_string())
+}
+
+fn parse_timestamp(s: &str) -> Result<u64, String> {
+    jwt_core::parse_timestamp(s).map_err(|e| e.to_string())
+}
+
+fn parse_date_window(s: &str) -> Result<DateWindow, String> {
+    s.parse().map_err(|e: jwt_core::Err| e.to_string())
+}
+
+fn parse_disclosure(s: &str) -> Result<LineItemDisclosure, String> {
+    s.parse().map_err(|e: jwt_core::Err| e.to_string())
+}
+
+pub fn read_input(path: &str) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    if path == STDIO {
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| Error::io("Could not read from stdin", e))?;
+    } else {
+        std::fs::File::open(path)
+            .and_then(|mut f| f.read_to_end(&mut bytes))
+            .map_err(|e| Error::io(format!("Could not read {path}"), e))?;
+    }
+    Ok(bytes)
+}
+
+fn read_revocation_lists(paths: &[String]) -> Result<Vec<PublishedRevocationList>, Error> {
+    paths
+        .iter()
+        .map(|path| Ok(serde_json::from_slice(&read_input(path)?)?))
+        .collect()
+}
+
+pub fn read_input_to_string(path: &str) -> Result<String, Error> {
+    String::from_utf8(read_input(path)?)
+        .map_err(|_| Error::BadInput(format!("{path} is not valid UTF-8")))
+}
+
+pub fn write_output(path: &str, bytes: &[u8]) -> Result<(), Error> {
+    if path == STDIO {
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(bytes)
+            .and_then(|_| stdout.flush())
+            .map_err(|e| Error::io("Could not write to stdout", e))
+    } else {
+        std::fs::write(path, bytes).map_err(|e| Error::io(format!("Could not write {path}"), e))
+    }
+}
+
+/// Runs a command, logging to stderr so that stdout stays usable in
+/// pipelines, and maps any error to a process exit code.
+pub fn main_with(command: Command) -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    match run(command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+pub fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Claims(args) => claims(args),
+        Command::Sign(args) => sign(args),
+        Command::Prove(args) => prove(args),
+        Command::Batch(args) => batch(args),
+        Command::Verify(args) => verify(args),
+        Command::Fingerprint(args) => fingerprint(args),
+        Command::Inspect(args) => inspect(args),
+        Command::Revocation(args) => match args.command {
+            RevocationCommand::Publish(args) => publish_revocation_list(args),
+            RevocationCommand::Hash(args) => {
+                let token = read_input_to_string(&args.token)?;
+                println!("{}", hash_to_hex(&token_hash(token.trim())));
+                Ok(())
+            }
+        },
+        Command::Keys(args) => keys(args.command),
+        Command::AuditKeygen(args) => {
+            let (secret_key, public_key) = generate_auditor_key()?;
+            write_output(&args.secret_key, secret_key.as_bytes())?;
+            write_output(&args.public_key, public_key.as_bytes())
+        }
+        Command::AuditDecrypt(args) => {
+            let receipt = deserialize_receipt(&read_input(&args.receipt)?)?;
+            let secret_key = read_input_to_string(&args.secret_key)?;
+            let claims = decrypt_receipt(&receipt, &secret_key)?;
+            write_output(
+                &args.output,
+                serde_json::to_string_pretty(&claims)?.as_bytes(),
+            )
+        }
+        Command::Cycles(args) => cycles(args),
+        Command::Completions(args) => {
+            clap_complete::generate(
+                args.shell,
+                &mut Cli::command(),
+                "zkpod",
+                &mut std::io::stdout(),
+            );
+            Ok(())
+        }
+    }
+}
+
+fn claims(args: ClaimsArgs) -> Result<(), Error> {
+    let delivery_date = args
+        .delivery_date
+        .unwrap_or_else(|| Local::now().date_naive());
+    if args.template == ClaimsTemplate::CarrierReceipt {
+        let receipt = CarrierReceipt {
+            carrier_id: args.carrier_id,
+            recipient_id: args.issuer_id,
+            reference: args.reference.unwrap_or_default(),
+            line_items: if args.line_items.is_empty() {
+                vec![LineItem {
+                    product: Some(args.product),
+                    quantity: Some(args.quantity),
+                    unit: None,
+                    unit_price: None,
+                }]
+            } else {
+                args.line_items
+            },
+            disclosure: args.disclose,
+            delivery_date,
+        };
+        let receipt_claims_string = serde_json::to_string_pretty(&receipt.to_claims()?)?;
+        return write_output(&args.path_to_claims_file, receipt_claims_string.as_bytes());
+    }
+
+    if !args.line_items.is_empty() {
+        let invoice = Invoice {
+            issuer_id: args.issuer_id,
+            subject_id: args.subject_id,
+            line_items: args.line_items,
+            disclosure: args.disclose,
+            delivery_date,
+        };
+        let invoice_claims_string = serde_json::to_string_pretty(&invoice.to_claims()?)?;
+        return write_output(&args.path_to_claims_file, invoice_claims_string.as_bytes());
+    }
+
+    let mut invoice_claims = CustomClaims::new();
+    invoice_claims.add("reference".to_string(), Uuid::new_v4().to_string(), false);
+    invoice_claims.add("issuer_id".to_string(), args.issuer_id, true);
+    invoice_claims.add("subject_id".to_string(), args.subject_id, false);
+    invoice_claims.add("product".to_string(), args.product, false);
+    invoice_claims.add("quantity".to_string(), args.quantity.to_string(), false);
+    invoice_claims.add_money("cost".to_string(), &args.cost, true);
+    add_delivery_date(&mut invoice_claims, delivery_date);
+
+    let invoice_claims_string = serde_json::to_string_pretty(&invoice_claims)?;
+    write_output(&args.path_to_claims_file, invoice_claims_string.as_bytes())
+}
+
+fn sign(args: SignArgs) -> Result<(), Error> {
+    let secret_key = read_input_to_string(&args.secret_key)?;
+    let claims: CustomClaims = serde_json::from_slice(&read_input(&args.claims)?)?;
+
+    let iss = secret_key.parse::<Issuer>()?;
+    let token = if args.delivery_receipt {
+        iss.sign_delivery_receipt(&claims)?
+    } else {
+        iss.generate_token(&claims)?
+    };
+
+    write_output(&args.token, token.as_bytes())
+}
+
+fn prove(args: ProveArgs) -> Result<(), Error> {
+    let token = read_input_to_string(&args.token)?;
+    let pks = args
+        .public_keys
+        .iter()
+        .map(|path| read_input_to_string(path))
+        .collect::<Result<Vec<String>, Error>>()?;
+
+    let mut options = args.statements.to_proof_options()?;
+    if let Some(path) = &args.delivery_receipt {
+        options.delivery_receipt = Some(DeliveryReceipt {
+            token: read_input_to_string(path)?.trim().to_string(),
+            public_keys: args
+                .carrier_keys
+                .iter()
+                .map(|path| read_input_to_string(path))
+                .collect::<Result<Vec<String>, Error>>()?,
+        });
+    }
+    let receipt = prove_token_validation_with(token.trim(), &pks, &options)?;
+
+    write_output(&args.receipt, &serialize_receipt(&receipt)?)
+}
+
+fn batch(args: BatchArgs) -> Result<(), Error> {
+    let pks = args
+        .public_keys
+        .iter()
+        .map(|path| read_input_to_string(path))
+        .collect::<Result<Vec<String>, Error>>()?;
+
+    let tokens = if args.tokens.is_dir() {
+        BatchManifest::from_token_dir(&args.tokens)?
+    } else {
+        BatchManifest::load(&args.tokens)?
+    };
+    let config = BatchConfig {
+        manifest_path: args
+            .manifest
+            .unwrap_or_else(|| args.out_dir.join(MANIFEST_FILE_NAME)),
+        out_dir: args.out_dir,
+        concurrency: args.concurrency,
+    };
+
+    // Resume from a previous run's manifest, picking up any new tokens.
+    let mut manifest = if Path::new(&config.manifest_path).exists() {
+        BatchManifest::load(&config.manifest_path)?
+    } else {
+        BatchManifest::default()
+    };
+    manifest.merge(tokens);
+    manifest.public_keys = args.public_keys.iter().map(PathBuf::from).collect();
+
+    let options = args.statements.to_proof_options()?;
+    let manifest = run_batch(manifest, &pks, &options, &config)?;
+
+    let failed = manifest.count(BatchStatus::Failed);
+    println!(
+        "Proved {} of {} tokens, manifest written to {}",
+        manifest.count(BatchStatus::Succeeded),
+        manifest.entries.len(),
+        config.manifest_path.display()
+    );
+    if failed > 0 {
+        return Err(Error::ProvingFailed(format!(
+            "{failed} tokens could not be proved, see the manifest for details"
+        )));
+    }
+    Ok(())
+}
+
+fn publish_revocation_list(args: PublishArgs) -> Result<(), Error> {
+    let secret_key = read_input_to_string(&args.secret_key)?;
+    let revoked = read_input_to_string(&args.revoked)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_revoked_entry)
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let published = PublishedRevocationList::publish(&secret_key, args.list_version, &revoked)?;
+    write_output(
+        &args.list,
+        serde_json::to_string_pretty(&published)?.as_bytes(),
+    )
+}
+
+fn keys(command: KeysCommand) -> Result<(), Error> {
+    match command {
+        KeysCommand::Show(args) => {
+            for path in args.keys.iter() {
+                let key = read_input_to_string(path)?;
+                println!(
+                    "{}: {} ({})",
+                    path,
+                    jwk_thumbprint(&key, ThumbprintHash::Sha256)?,
+                    KeyValidity::from_jwk(&key)?
+                );
+            }
+            Ok(())
+        }
+        KeysCommand::Window(args) => {
+            let key = read_input_to_string(&args.key)?;
+            let validity = KeyValidity {
+                not_before: args.not_before,
+                not_after: args.not_after,
+            };
+            write_output(&args.output, with_validity(&key, &validity)?.as_bytes())
+        }
+        KeysCommand::Rotate(args) => {
+            let previous = read_input_to_string(&args.previous_secret_key)?;
+            let next = read_input_to_string(&args.next_key)?;
+            let at = args
+                .at
+                .unwrap_or_else(|| chrono::Utc::now().timestamp().max(0) as u64);
+            write_output(&args.record, rotate(&previous, &next, at)?.as_bytes())
+        }
+        KeysCommand::Chain(args) => {
+            let keys = args
+                .keys
+                .iter()
+                .map(|path| read_input_to_string(path))
+                .collect::<Result<Vec<String>, Error>>()?;
+            let rotations = args
+                .rotations
+                .iter()
+                .map(|path| Ok(read_input_to_string(path)?.trim().to_string()))
+                .collect::<Result<Vec<String>, Error>>()?;
+
+            std::fs::create_dir_all(&args.out_dir).map_err(|e| {
+                Error::io(format!("Could not create {}", args.out_dir.display()), e)
+            })?;
+            for (path, key) in args.keys.iter().zip(apply_rotations(&keys, &rotations)?) {
+                let file_name = Path::new(path).file_name().ok_or_else(|| {
+                    Error::BadInput(format!("Key path '{path}' has no file name"))
+                })?;
+                let out = args.out_dir.join(file_name);
+                write_output(&out.to_string_lossy(), key.as_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn verify(args: VerifyArgs) -> Result<(), Error> {
+    let revocation_lists = read_revocation_lists(&args.revocation_lists)?;
+    let mut report = VerificationReport::from_receipt_bytes(&read_input(&args.receipt)?);
+    report.check_revocation_lists(&revocation_lists);
+    if !args.key_set.is_empty() {
+        let mut keys = Vec::new();
+        for path in args.key_set.iter() {
+            keys.extend(load_keys(Path::new(path))?.into_iter().map(|key| key.jwk));
+        }
+        let rotations = args
+            .rotations
+            .iter()
+            .map(|path| Ok(read_input_to_string(path)?.trim().to_string()))
+            .collect::<Result<Vec<String>, Error>>()?;
+        report.check_key_validity(&apply_rotations(&keys, &rotations)?);
+    }
+
+    match args.format {
+        OutputFormat::Text => println!("{}", report.to_text()),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    report.into_result().map(|_| ())
+}
+
+fn cycles(args: CyclesArgs) -> Result<(), Error> {
+    let token = read_input_to_string(&args.token)?;
+    let signing_key = read_input_to_string(&args.signing_key)?;
+    let counts = run_cycle_bench(token.trim(), &signing_key, &args.key_counts)?;
+
+    match args.format {
+        OutputFormat::Text => {
+            println!(
+                "{:>6} {:>15} {:>12} {:>14}",
+                "keys", "signer position", "cycles", "try each key"
+            );
+            for count in counts.iter() {
+                println!(
+                    "{:>6} {:>15} {:>12} {:>14}",
+                    count.keys,
+                    count.signer_position + 1,
+                    count.cycles,
+                    count.try_each_key_cycles
+                );
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&counts)?),
+    }
+    Ok(())
+}
+
+fn fingerprint(args: FingerprintArgs) -> Result<(), Error> {
+    let pk = read_input_to_string(&args.key)?;
+    let fingerprint = if args.uri {
+        jwk_thumbprint_uri(&pk, args.hash)?
+    } else {
+        jwk_thumbprint(&pk, args.hash)?
+    };
+    println!("{}", fingerprint);
+    Ok(())
+}
+
+fn inspect(args: InspectArgs) -> Result<(), Error> {
+    let token = read_input_to_string(&args.token)?;
+    let mut keys = Vec::new();
+    for path in args.keys.iter() {
+        keys.extend(load_keys(Path::new(path))?);
+    }
+    let inspection = TokenInspection::new(&token, &keys)?;
+
+    match args.format {
+        OutputFormat::Text => println!("{}", inspection.to_text()),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&inspection)?),
+    }
+    inspection.into_result().map(|_| ())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_error_exit_codes() {
+        assert_eq!(
+            Error::VerificationFailed("bad seal".to_string()).exit_code(),
+            EXIT_VERIFICATION_FAILED
+        );
+        assert_eq!(
+            Error::BadInput("not a receipt".to_string()).exit_code(),
+            EXIT_BAD_INPUT
+        );
+        assert_eq!(
+            Error::io(
+                "Could not read",
+                std::io::Error::from(std::io::ErrorKind::NotFound)
+            )
+            .exit_code(),
+            EXIT_IO_ERROR
+        );
+    }
+
+    #[test]
+    fn test_claims_line_items() {
+        let cli = Cli::try_parse_from([
+            "zkpod",
+            "claims",
+            "-l",
+            "raw coffee beans:1000:kg:4.00 GBP",
+            "--line-item",
+            "decaf beans:250:kg:5.20 GBP",
+            "--disclose",
+            "product,unit",
+        ])
+        .unwrap();
+        let Command::Claims(args) = cli.command else {
+            panic!("expected the claims subcommand");
+        };
+        assert_eq!(args.line_items.len(), 2);
+        assert!(args.disclose.unit && !args.disclose.total_quantity);
+
+        assert!(Cli::try_parse_from(["zkpod", "claims", "-l", "raw coffee beans"]).is_err());
+    }
+
+    #[test]
+    fn test_prove_requires_public_keys() {
+        assert!(Cli::try_parse_from(["zkpod", "prove", "pod.jwt", "zkpod.bin"]).is_err());
+        assert!(Cli::try_parse_from(["zkpod", "prove", "pod.jwt", "zkpod.bin", "pk.jwk"]).is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+//! Inspection of a proof of delivery before proving it: what the proof would
+//! reveal, and which candidate key, if any, signed it.
+
+use crate::key_set::LoadedKey;
+use crate::Error;
+use jwt_core::{decode_untrusted_token, ClaimItem, Validator};
+use serde_json::{Map, Value};
+
+/// Outcome of checking the token's signature with one candidate key.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyCheck {
+    pub label: String,
+    pub fingerprint: Option<String>,
+    /// Why the token does not verify with the key; none if it does.
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct TokenInspection {
+    pub header: Value,
+    /// The claims as signed, without checking the signature.
+    pub claims: Value,
+    /// What a receipt for the token would disclose.
+    pub public_claims: Map<String, Value>,
+    /// Keys of the claims a receipt would hide.
+    pub private_claims: Vec<String>,
+    pub keys: Vec<KeyCheck>,
+    /// Index in `keys` of the first key the token verifies with.
+    pub signer: Option<usize>,
+    #[serde(skip)]
+    pub items: Vec<ClaimItem>,
+}
+
+impl TokenInspection {
+    /// Decodes `token` and checks it against each of `keys` as the guest
+    /// would, including the token type, `kid` and key validity window.
+    pub fn new(token: &str, keys: &[LoadedKey]) -> Result<Self, Error> {
+        let token = token.trim();
+        let (header, claims) = decode_untrusted_token(token)?;
+        let items = claims.custom.items().to_vec();
+
+        let keys: Vec<KeyCheck> = keys
+            .iter()
+            .map(|key| match key.jwk.parse::<Validator>() {
+                Ok(validator) => KeyCheck {
+                    label: key.label.clone(),
+                    error: validator
+                        .validate_token_integrity(token)
+                        .err()
+                        .map(|e| e.to_string()),
+                    fingerprint: Some(validator.thumbprint),
+                },
+                Err(e) => KeyCheck {
+                    label: key.label.clone(),
+                    fingerprint: None,
+                    error: Some(e.to_string()),
+                },
+            })
+            .collect();
+
+        Ok(TokenInspection {
+            header: serde_json::to_value(&header)?,
+            public_claims: claims.custom.get_public_claims().to_json_object(),
+            private_claims: items
+                .iter()
+                .filter(|item| item.is_private)
+                .map(|item| item.key.clone())
+                .collect(),
+            claims: serde_json::to_value(&claims)?,
+            signer: keys.iter().position(|check| check.error.is_none()),
+            keys,
+            items,
+        })
+    }
+
+    /// Fails if keys were given and none of them signed the token, so that
+    /// proving with them would fail.
+    pub fn into_result(self) -> Result<Self, Error> {
+        if !self.keys.is_empty() && self.signer.is_none() {
+            return Err(Error::VerificationFailed(
+                "the token does not verify with any of the keys".to_string(),
+            ));
+        }
+        Ok(self)
+    }
+
+    fn header_member(&self, member: &str) -> &str {
+        self.header
+            .get(member)
+            .and_then(Value::as_str)
+            .unwrap_or("none")
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Token of type {}, naming key {}\n",
+            self.header_member("typ"),
+            self.header_member("kid"),
+        );
+
+        text += "\nClaims:";
+        for item in self.items.iter() {
+            let visibility = if item.is_private { "private" } else { "public" };
+            text += &format!("\n  {visibility:8} {}: {}", item.key, item.value);
+        }
+
+        text += "\n\nA receipt would disclose:\n";
+        text += &serde_json::to_string_pretty(&self.public_claims)
+            .expect("a JSON map always serialises");
+
+        if self.keys.is_empty() {
+            return text;
+        }
+        text += "\n\nCandidate keys:";
+        for check in self.keys.iter() {
+            let fingerprint = check.fingerprint.as_deref().unwrap_or("no fingerprint");
+            match &check.error {
+                None => text += &format!("\n  ok   {} ({fingerprint})", check.label),
+                Some(e) => text += &format!("\n  fail {} ({fingerprint}): {e}", check.label),
+            }
+        }
+        text += &match self.signer {
+            Some(index) => format!(
+                "\n\nThe token verifies with {}, so proving with these keys will succeed.",
+                self.keys[index].label
+            ),
+            None => "\n\nThe token does not verify with any of these keys, so proving with them will fail.".to_string(),
+        };
+        text
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jwt_core::{CustomClaims, Issuer};
+
+    const SK: &str = include_str!("../../test_data/coffee_company_sk.jwk");
+    const PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+    const OTHER_PK: &str = include_str!("../../test_data/other_pk_1.jwk");
+
+    fn key(label: &str, jwk: &str) -> LoadedKey {
+        LoadedKey {
+            label: label.to_string(),
+            jwk: jwk.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_inspect_token() {
+        let mut claims = CustomClaims::new();
+        claims.add("issuer_id".to_string(), "Coffee Chain 1".to_string(), false);
+        claims.add("cost".to_string(), "4000.00 GBP".to_string(), true);
+        let token = SK
+            .parse::<Issuer>()
+            .unwrap()
+            .generate_token(&claims)
+            .unwrap();
+
+        let keys = [key("other", OTHER_PK), key("coffee", PK), key("junk", "{}")];
+        let inspection = TokenInspection::new(&token, &keys).unwrap();
+        assert_eq!(inspection.header["typ"], "pod+jwt");
+        assert_eq!(inspection.public_claims["issuer_id"], "Coffee Chain 1");
+        assert_eq!(inspection.private_claims, vec!["cost".to_string()]);
+        assert_eq!(inspection.signer, Some(1));
+        assert!(inspection.keys[0].error.is_some());
+        assert!(inspection.keys[2].fingerprint.is_none());
+        assert!(inspection.to_text().contains("private  cost: 4000.00 GBP"));
+        assert!(inspection.to_text().contains("will succeed"));
+        assert!(inspection.into_result().is_ok());
+
+        let inspection = TokenInspection::new(&token, &keys[..1]).unwrap();
+        assert!(inspection.to_text().contains("will fail"));
+        assert!(matches!(
+            inspection.into_result(),
+            Err(Error::VerificationFailed(_))
+        ));
+
+        assert!(TokenInspection::new(&token, &[])
+            .unwrap()
+            .into_result()
+            .is_ok());
+        assert!(TokenInspection::new("not a token", &[]).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Invoice and carrier receipt claims shared by the command line interface
+//! and the TUI.
+
+use chrono::NaiveDate;
+use jwt_core::statement::DELIVERY_DATE_CLAIM;
+use jwt_core::{total_quantity, CustomClaims, LineItem, LineItemDisclosure, Money};
+use uuid::Uuid;
+
+pub const DEFAULT_DISCLOSURE: &str = "product,total_quantity";
+
+pub struct Invoice {
+    pub issuer_id: String,
+    pub subject_id: String,
+    pub line_items: Vec<LineItem>,
+    pub disclosure: LineItemDisclosure,
+    pub delivery_date: NaiveDate,
+}
+
+impl Invoice {
+    /// Builds the claims to be signed, with a fresh public reference.  The
+    /// issuer, the delivery date and the invoice totals, `cost` and
+    /// `quantity`, are kept private; line item fields are disclosed according
+    /// to `disclosure`.  Lines in different units have no `quantity` total,
+    /// and then the total quantity may not be disclosed.
+    pub fn to_claims(&self) -> Result<CustomClaims, jwt_core::Err> {
+        let mut claims = CustomClaims::new();
+        claims.add("reference".to_string(), Uuid::new_v4().to_string(), false);
+        claims.add("issuer_id".to_string(), self.issuer_id.clone(), true);
+        claims.add("subject_id".to_string(), self.subject_id.clone(), false);
+        add_delivery_date(&mut claims, self.delivery_date);
+
+        let mut cost: Option<Money> = None;
+        for line_item in self.line_items.iter() {
+            if let Some(total) = line_item.total() {
+                let total = total?;
+                cost = Some(match cost {
+                    Some(cost) => cost.checked_add(&total)?,
+                    None => total,
+                });
+            }
+            claims.add_line_item(line_item.clone());
+        }
+        if let Some(cost) = cost {
+            claims.add_money("cost".to_string(), &cost, true);
+        }
+        match total_quantity(&self.line_items) {
+            Ok(quantity) => claims.add("quantity".to_string(), quantity.to_string(), true),
+            Err(jwt_core::Err::MixedUnits(_, _)) if !self.disclosure.total_quantity => {}
+            Err(e) => return Err(e),
+        }
+        claims.set_line_item_disclosure(self.disclosure);
+
+        Ok(claims)
+    }
+}
+
+/// A carrier's or warehouse's confirmation that the goods on the invoice
+/// with `reference` were delivered to `recipient_id`.
+pub struct CarrierReceipt {
+    pub carrier_id: String,
+    pub recipient_id: String,
+    pub reference: String,
+    pub line_items: Vec<LineItem>,
+    pub disclosure: LineItemDisclosure,
+    pub delivery_date: NaiveDate,
+}
+
+impl CarrierReceipt {
+    /// Builds the claims to be signed by the carrier.  The reference and the
+    /// delivery status are public; the carrier, the recipient and the
+    /// delivery date are kept private, and line item fields are disclosed
+    /// according to `disclosure`.  The total quantity may only be disclosed
+    /// if the lines are in the same unit.
+    pub fn to_claims(&self) -> Result<CustomClaims, jwt_core::Err> {
+        if self.disclosure.total_quantity {
+            total_quantity(&self.line_items)?;
+        }
+
+        let mut claims = CustomClaims::new();
+        claims.add("reference".to_string(), self.reference.clone(), false);
+        claims.add("carrier_id".to_string(), self.carrier_id.clone(), true);
+        claims.add("recipient_id".to_string(), self.recipient_id.clone(), true);
+        claims.add("status".to_string(), "delivered".to_string(), false);
+        add_delivery_date(&mut claims, self.delivery_date);
+        for line_item in self.line_items.iter() {
+            claims.add_line_item(LineItem {
+                unit_price: None,
+                ..line_item.clone()
+            });
+        }
+        claims.set_line_item_disclosure(self.disclosure);
+        Ok(claims)
+    }
+}
+
+/// Adds the private delivery date claim, which can be proved to lie in a
+/// window with a [`jwt_core::DateWindow`].
+pub fn add_delivery_date(claims: &mut CustomClaims, delivery_date: NaiveDate) {
+    claims.add(
+        DELIVERY_DATE_CLAIM.to_string(),
+        delivery_date.to_string(),
+        true,
+    );
+}
+
+/// Parses line items separated by semicolons, as entered in a single field.
+pub fn parse_line_items(s: &str) -> Result<Vec<LineItem>, jwt_core::Err> {
+    s.split(';')
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_invoice_claims() {
+        let invoice = Invoice {
+            issuer_id: "Coffee Chain 1".to_string(),
+            subject_id: "Coffee Supplier".to_string(),
+            line_items: parse_line_items(
+                "raw coffee beans:1000:kg:4.00 GBP; decaf beans:250:kg:5.20 GBP",
+            )
+            .unwrap(),
+            disclosure: DEFAULT_DISCLOSURE.parse().unwrap(),
+            delivery_date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+        };
+
+        let claims = invoice.to_claims().unwrap();
+        assert_eq!(claims.get("cost"), Some("5300.00 GBP"));
+        assert_eq!(claims.get("quantity"), Some("1250"));
+        assert_eq!(claims.get("delivery_date"), Some("2026-03-14"));
+
+        let public_claims = claims.get_public_claims().to_json_object();
+        assert!(public_claims.contains_key("reference"));
+        assert!(!public_claims.contains_key("issuer_id"));
+        assert_eq!(public_claims["total_quantity"], "1250");
+        assert_eq!(public_claims["line_items"][1]["product"], "decaf beans");
+        assert!(public_claims["line_items"][1].get("unit_price").is_none());
+        assert!(!public_claims.contains_key("cost"));
+        assert!(!public_claims.contains_key("delivery_date"));
+    }
+
+    #[test]
+    fn test_carrier_receipt_claims() {
+        let receipt = CarrierReceipt {
+            carrier_id: "Coffee Haulage".to_string(),
+            recipient_id: "Coffee Chain 1".to_string(),
+            reference: "PO-1".to_string(),
+            line_items: parse_line_items("raw coffee beans:1000:kg:4.00 GBP").unwrap(),
+            disclosure: DEFAULT_DISCLOSURE.parse().unwrap(),
+            delivery_date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+        };
+
+        let public_claims = receipt
+            .to_claims()
+            .unwrap()
+            .get_public_claims()
+            .to_json_object();
+        assert_eq!(public_claims["reference"], "PO-1");
+        assert_eq!(public_claims["status"], "delivered");
+        assert_eq!(public_claims["total_quantity"], "1000");
+        assert!(!public_claims.contains_key("carrier_id"));
+        assert!(!public_claims.contains_key("recipient_id"));
+    }
+
+    #[test]
+    fn test_invoice_rejects_mixed_currencies() {
+        let invoice = Invoice {
+            issuer_id: "Coffee Chain 1".to_string(),
+            subject_id: "Coffee Supplier".to_string(),
+            line_items: parse_line_items("beans:1:kg:4.00 GBP; beans:1:kg:4.00 EUR").unwrap(),
+            disclosure: LineItemDisclosure::default(),
+            delivery_date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+        };
+        assert!(matches!(
+            invoice.to_claims(),
+            Err(jwt_core::Err::CurrencyMismatch(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_invoice_quantities_in_different_units() {
+        let mut invoice = Invoice {
+            issuer_id: "Coffee Chain 1".to_string(),
+            subject_id: "Coffee Supplier".to_string(),
+            line_items: parse_line_items("beans:1000:kg:4.00 GBP; sacks:20:bag:1.00 GBP").unwrap(),
+            disclosure: "product".parse().unwrap(),
+            delivery_date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+        };
+        let claims = invoice.to_claims().unwrap();
+        assert_eq!(claims.get("quantity"), None);
+        assert_eq!(claims.get("cost"), Some("4020.00 GBP"));
+
+        invoice.disclosure = DEFAULT_DISCLOSURE.parse().unwrap();
+        assert!(matches!(
+            invoice.to_claims(),
+            Err(jwt_core::Err::MixedUnits(_, _))
+        ));
+
+        invoice.line_items = vec![
+            format!("beans:{}:kg:0.00 GBP", u64::MAX).parse().unwrap(),
+            "beans:1:kg:0.00 GBP".parse().unwrap(),
+        ];
+        assert!(matches!(
+            invoice.to_claims(),
+            Err(jwt_core::Err::QuantityOverflow)
+        ));
+    }
+}
@@ -0,0 +1,342 @@
+//! Key set tooling: loading candidate keys, their validity windows, the
+//! rotation chains they are derived from and the canonical order the guest
+//! requires.
+
+use crate::{compute_fingerprint, Error};
+use jwt_core::{CompactKey, Issuer, KeyRotationClaims, KeyValidity, Validator};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// A candidate key and where it came from.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LoadedKey {
+    /// File name, followed by the key's `kid` or position for a JWKS.
+    pub label: String,
+    pub jwk: String,
+}
+
+/// Loads the keys in a JWK file, in a JWKS file (`{"keys": [...]}`) or in
+/// every `*.jwk` file of a directory, in file name order.
+pub fn load_keys(path: &Path) -> Result<Vec<LoadedKey>, Error> {
+    if path.is_dir() {
+        let mut files = std::fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| Error::io(format!("Could not read {}", path.display()), e))?;
+        files.retain(|file| file.extension().is_some_and(|extension| extension == "jwk"));
+        files.sort();
+        let mut keys = Vec::new();
+        for file in files.iter() {
+            keys.extend(load_keys(file)?);
+        }
+        return Ok(keys);
+    }
+
+    let name = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| Error::io(format!("Could not read {}", path.display()), e))?;
+    let mut members: Map<String, Value> = serde_json::from_str(&contents)?;
+    match members.remove("keys") {
+        Some(Value::Array(keys)) => keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let label = match key.get("kid").and_then(Value::as_str) {
+                    Some(kid) => format!("{name}#{kid}"),
+                    None => format!("{name}#{}", index + 1),
+                };
+                Ok(LoadedKey {
+                    label,
+                    jwk: serde_json::to_string_pretty(key)?,
+                })
+            })
+            .collect(),
+        Some(_) => Err(Error::BadInput(format!(
+            "{} has a 'keys' member that is not an array",
+            path.display()
+        ))),
+        None => Ok(vec![LoadedKey {
+            label: name,
+            jwk: contents,
+        }]),
+    }
+}
+
+/// Returns the JWK with its `not_before` and `not_after` members replaced by
+/// those of `validity`.
+pub fn with_validity(jwk: &str, validity: &KeyValidity) -> Result<String, Error> {
+    let mut members: Map<String, Value> = serde_json::from_str(jwk)?;
+    for (member, timestamp) in [
+        ("not_before", validity.not_before),
+        ("not_after", validity.not_after),
+    ] {
+        match timestamp {
+            Some(timestamp) => members.insert(member.to_string(), timestamp.into()),
+            None => members.remove(member),
+        };
+    }
+    Ok(serde_json::to_string_pretty(&members)?)
+}
+
+/// Signs a record, with the previous key's secret JWK, handing over to the
+/// next key from `effective_at` onwards.
+pub fn rotate(
+    previous_secret_key: &str,
+    next_key: &str,
+    effective_at: u64,
+) -> Result<String, Error> {
+    let claims = KeyRotationClaims {
+        previous: compute_fingerprint(previous_secret_key)?,
+        next: compute_fingerprint(next_key)?,
+        effective_at,
+    };
+    Ok(previous_secret_key
+        .parse::<Issuer>()?
+        .sign_key_rotation(&claims)?)
+}
+
+/// Applies rotation records to a key set: each record ends the window of
+/// the key that signed it and starts that of its successor.  The keys are
+/// returned in the same order, with their windows narrowed accordingly.
+pub fn apply_rotations(keys: &[String], rotations: &[String]) -> Result<Vec<String>, Error> {
+    let fingerprints = keys
+        .iter()
+        .map(|key| compute_fingerprint(key))
+        .collect::<Result<Vec<String>, jwt_core::Err>>()?;
+    let mut validities = keys
+        .iter()
+        .map(|key| KeyValidity::from_jwk(key))
+        .collect::<Result<Vec<KeyValidity>, jwt_core::Err>>()?;
+    let mut rotated = vec![false; keys.len()];
+
+    let position = |fingerprint: &str| {
+        fingerprints
+            .iter()
+            .position(|candidate| candidate == fingerprint)
+            .ok_or_else(|| {
+                Error::BadInput(format!(
+                    "Key {fingerprint} of a rotation record is not in the key set"
+                ))
+            })
+    };
+
+    for record in rotations.iter() {
+        let (signer, claims) = keys
+            .iter()
+            .enumerate()
+            .find_map(|(index, key)| {
+                let validator = key.parse::<Validator>().ok()?;
+                Some((index, validator.validate_key_rotation(record).ok()?))
+            })
+            .ok_or_else(|| {
+                Error::BadInput(
+                    "Rotation record is not signed by any key in the key set".to_string(),
+                )
+            })?;
+
+        // The record must be signed by the key it retires.
+        let previous = position(&claims.previous)?;
+        let next = position(&claims.next)?;
+        if signer != previous {
+            return Err(Error::BadInput(format!(
+                "Rotation record for key {} is signed by another key",
+                claims.previous
+            )));
+        }
+        if std::mem::replace(&mut rotated[previous], true) {
+            return Err(Error::BadInput(format!(
+                "Key {} is rotated more than once",
+                claims.previous
+            )));
+        }
+
+        let validity = &mut validities[previous];
+        validity.not_after = Some(
+            validity
+                .not_after
+                .map_or(claims.effective_at, |t| t.min(claims.effective_at)),
+        );
+        let validity = &mut validities[next];
+        validity.not_before = Some(
+            validity
+                .not_before
+                .map_or(claims.effective_at, |t| t.max(claims.effective_at)),
+        );
+    }
+
+    keys.iter()
+        .zip(validities.iter())
+        .zip(fingerprints.iter())
+        .map(|((key, validity), fingerprint)| {
+            if let (Some(not_before), Some(not_after)) = (validity.not_before, validity.not_after) {
+                if not_before >= not_after {
+                    return Err(Error::BadInput(format!(
+                        "Key {fingerprint} is never valid after applying the rotations"
+                    )));
+                }
+            }
+            with_validity(key, validity)
+        })
+        .collect()
+}
+
+/// The distinct keys of a key set in the order the guest requires, sorted
+/// by thumbprint, each with the position of its first occurrence in `keys`.
+pub fn canonical_key_set(keys: &[String]) -> Result<Vec<(usize, CompactKey)>, Error> {
+    let mut canonical: Vec<(String, usize, CompactKey)> = Vec::new();
+    for (index, key) in keys.iter().enumerate() {
+        let compact = CompactKey::from_jwk(key)
+            .and_then(|compact| compact.validator().map(|_| compact))
+            .map_err(|e| Error::BadInput(format!("Public key {} is not usable: {e}", index + 1)))?;
+        let thumbprint = compact.thumbprint();
+        match canonical.iter().find(|(other, _, _)| *other == thumbprint) {
+            Some((_, _, other)) if other.validity != compact.validity => {
+                return Err(Error::BadInput(format!(
+                    "Key {thumbprint} is given twice with different validity windows"
+                )));
+            }
+            Some((_, _, other)) if other.metadata != compact.metadata => {
+                return Err(Error::BadInput(format!(
+                    "Key {thumbprint} is given twice with different use, key_ops, alg or kid"
+                )));
+            }
+            Some(_) => {}
+            None => canonical.push((thumbprint, index, compact)),
+        }
+    }
+    canonical.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(canonical
+        .into_iter()
+        .map(|(_, index, compact)| (index, compact))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jwt_core::CustomClaims;
+
+    const SK: &str = include_str!("../../test_data/coffee_company_sk.jwk");
+    const PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+    const NEXT_PK: &str = include_str!("../../test_data/other_pk_1.jwk");
+
+    #[test]
+    fn test_rotation_chain() {
+        let record = rotate(SK, NEXT_PK, 1000).unwrap();
+        let keys = apply_rotations(
+            &[PK.to_string(), NEXT_PK.to_string()],
+            std::slice::from_ref(&record),
+        )
+        .unwrap();
+
+        assert_eq!(
+            KeyValidity::from_jwk(&keys[0]).unwrap(),
+            KeyValidity {
+                not_before: None,
+                not_after: Some(1000)
+            }
+        );
+        assert_eq!(
+            KeyValidity::from_jwk(&keys[1]).unwrap().not_before,
+            Some(1000)
+        );
+        assert_eq!(
+            compute_fingerprint(&keys[0]).unwrap(),
+            compute_fingerprint(PK).unwrap()
+        );
+
+        // Tokens issued by the retired key after the rotation are rejected.
+        let issuer = SK.parse::<Issuer>().unwrap();
+        let validator = keys[0].parse::<Validator>().unwrap();
+        let claims = CustomClaims::new();
+        let before = issuer
+            .generate_token_at(&claims, chrono::DateTime::from_timestamp(999, 0).unwrap())
+            .unwrap();
+        assert!(validator.validate_token_integrity(&before).is_ok());
+        let after = issuer.generate_token(&claims).unwrap();
+        assert!(matches!(
+            validator.validate_token_integrity(&after),
+            Err(jwt_core::Err::KeyNotValidAt(_))
+        ));
+
+        assert!(matches!(
+            apply_rotations(
+                &[PK.to_string(), NEXT_PK.to_string()],
+                &[record.clone(), record]
+            ),
+            Err(Error::BadInput(_))
+        ));
+        assert!(matches!(
+            apply_rotations(
+                &[NEXT_PK.to_string()],
+                &[rotate(SK, NEXT_PK, 1000).unwrap()]
+            ),
+            Err(Error::BadInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_canonical_key_set() {
+        let keys = [NEXT_PK.to_string(), PK.to_string(), NEXT_PK.to_string()];
+        let canonical = canonical_key_set(&keys).unwrap();
+        assert_eq!(canonical.len(), 2);
+        assert!(canonical[0].1.thumbprint() < canonical[1].1.thumbprint());
+        assert!(canonical.iter().all(|(index, _)| *index < 2));
+
+        let windowed = with_validity(
+            PK,
+            &KeyValidity {
+                not_before: Some(1000),
+                not_after: None,
+            },
+        )
+        .unwrap();
+        assert!(matches!(
+            canonical_key_set(&[PK.to_string(), windowed]),
+            Err(Error::BadInput(_))
+        ));
+        assert!(matches!(
+            canonical_key_set(&[r#"{"kty": "RSA"}"#.to_string()]),
+            Err(Error::BadInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_keys() {
+        let keys = load_keys(Path::new("../test_data")).unwrap();
+        assert_eq!(
+            keys.iter()
+                .map(|key| key.label.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "coffee_company_pk.jwk",
+                "coffee_company_sk.jwk",
+                "other_pk_1.jwk",
+                "other_pk_2.jwk"
+            ]
+        );
+        assert_eq!(keys[0].jwk, PK);
+
+        let jwks = std::env::temp_dir().join(format!("zkpod-jwks-{}.json", std::process::id()));
+        std::fs::write(
+            &jwks,
+            format!(r#"{{"keys": [{PK}, {{"kty": "RSA", "kid": "k2"}}]}}"#),
+        )
+        .unwrap();
+        let keys = load_keys(&jwks).unwrap();
+        std::fs::remove_file(&jwks).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert!(keys[0].label.ends_with("#6ab0e8e4bc121fc287e35d3e5e0efb8a"));
+        assert!(keys[1].label.ends_with("#k2"));
+        assert_eq!(
+            compute_fingerprint(&keys[0].jwk).unwrap(),
+            compute_fingerprint(PK).unwrap()
+        );
+    }
+}
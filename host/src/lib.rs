@@ -16,86 +16,286 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use base64::prelude::*;
-use methods::VERIFY_TOKEN_WITH_SOME_KEY_ELF;
-use risc0_zkvm::sha::rust_crypto::Sha256;
-use risc0_zkvm::{default_prover, ExecutorEnv, Receipt};
-use serde::Serialize;
-use serde_json::Value;
-use sha2::Digest;
-
-#[derive(Serialize)]
-struct FingerprintableJwk {
-    e: String,
-    kty: String,
-    n: String,
+use borsh::{BorshDeserialize, BorshSerialize};
+use jwt_core::{
+    decode_untrusted_token, jwk_thumbprint, AuditCiphertext, AuditSeed, CompactKey,
+    DeliveryReceipt, NonMembershipProof, PublicOutput, Statements, ThumbprintHash, Validator,
+};
+use key_set::canonical_key_set;
+use methods::{TRY_EACH_KEY_ELF, VERIFY_TOKEN_WITH_SOME_KEY_ELF, VERIFY_TOKEN_WITH_SOME_KEY_ID};
+use revocation::PublishedRevocationList;
+use risc0_zkvm::{default_executor, default_prover, ExecutorEnv, Receipt};
+use thiserror::Error;
+
+pub mod audit;
+pub mod batch;
+pub mod bench;
+pub mod cli;
+pub mod inspect;
+pub mod invoice;
+pub mod key_set;
+pub mod report;
+pub mod revocation;
+#[cfg(feature = "server")]
+pub mod server;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Jwt(#[from] jwt_core::Err),
+
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid input: {0}")]
+    BadInput(String),
+
+    #[error("Failed to prove: {0}")]
+    ProvingFailed(String),
+
+    #[error("Verification failed: {0}")]
+    VerificationFailed(String),
+}
+
+impl Error {
+    pub fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+}
+
+pub fn compute_fingerprint(pk: &str) -> Result<String, jwt_core::Err> {
+    jwk_thumbprint(pk, ThumbprintHash::Sha256)
+}
+
+/// What to prove about a token besides its signature.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct ProofOptions {
+    #[serde(default)]
+    pub statements: Statements,
+    /// Current revocation list of each candidate key, in the same order as
+    /// the keys, or none to skip the revocation check.
+    #[serde(default)]
+    pub revocation_lists: Vec<PublishedRevocationList>,
+    /// Carrier's receipt confirming that the invoiced goods were delivered.
+    #[serde(default)]
+    pub delivery_receipt: Option<DeliveryReceipt>,
 }
 
-pub fn compute_fingerprint(pk: String) -> String {
-    let public_key: Value = serde_json::from_str(&pk).expect("Could not parse key");
+/// Index of the candidate key that signed the token.
+pub fn find_signer(token: &str, pks: &[String]) -> Option<usize> {
+    pks.iter().position(|pk| {
+        pk.parse::<Validator>()
+            .is_ok_and(|validator| validator.validate_token_integrity(token).is_ok())
+    })
+}
 
-    let e = public_key
-        .get("e")
-        .expect("Could not find mandatory field 'e'")
-        .to_string()
-        .replace("\"", "");
+pub fn prove_token_validation(token: &str, pks: &[String]) -> Result<Receipt, Error> {
+    prove_token_validation_with(token, pks, &ProofOptions::default())
+}
 
-    let kty = public_key
-        .get("kty")
-        .expect("Could not find mandatory field 'kty'")
-        .to_string()
-        .replace("\"", "");
+/// Proves that the token was signed by one of `pks`, that the statements in
+/// `options` hold for its claims and that it is not revoked.
+pub fn prove_token_validation_with(
+    token: &str,
+    pks: &[String],
+    options: &ProofOptions,
+) -> Result<Receipt, Error> {
+    let env = guest_env(token, pks, options)?;
 
-    let n = public_key
-        .get("n")
-        .expect("Could not find mandatory field 'n'")
-        .to_string()
-        .replace("\"", "");
+    let prover = default_prover();
 
-    let fingerprintable_jwk = FingerprintableJwk { e, kty, n };
+    let receipt = prover
+        .prove(env, VERIFY_TOKEN_WITH_SOME_KEY_ELF)
+        .map_err(|e| Error::ProvingFailed(e.to_string()))?
+        .receipt;
 
-    let fingerprintable_jwk_as_str = serde_json::to_string(&fingerprintable_jwk).unwrap();
+    Ok(receipt)
+}
 
-    let digest = Sha256::digest(fingerprintable_jwk_as_str);
+/// Runs the guest without proving and returns the number of user cycles it
+/// took, which is what proving time grows with.
+pub fn count_cycles(token: &str, pks: &[String], options: &ProofOptions) -> Result<u64, Error> {
+    let env = guest_env(token, pks, options)?;
+    let session = default_executor()
+        .execute(env, VERIFY_TOKEN_WITH_SOME_KEY_ELF)
+        .map_err(|e| Error::ProvingFailed(e.to_string()))?;
+    Ok(session.cycles())
+}
 
-    BASE64_URL_SAFE.encode(digest).replace("=", "")
+/// Like [`count_cycles`], but for the previous guest, which parses each key
+/// as a JWK and tries the keys in the given order until one validates the
+/// token, as a baseline to compare with.
+pub fn count_cycles_trying_each_key(token: &str, pks: &[String]) -> Result<u64, Error> {
+    let mut env = ExecutorEnv::builder();
+    env.write(&token)
+        .map_err(|e| Error::ProvingFailed(format!("Could not write JWT to env: {e}")))?;
+    env.write(&pks.len()).map_err(|e| {
+        Error::ProvingFailed(format!("Could not write number of public keys to env: {e}"))
+    })?;
+    for pk in pks.iter() {
+        env.write(pk)
+            .map_err(|e| Error::ProvingFailed(format!("Could not write pk to env: {e}")))?;
+    }
+    let env = env
+        .build()
+        .map_err(|e| Error::ProvingFailed(format!("Could not build env: {e}")))?;
+    let session = default_executor()
+        .execute(env, TRY_EACH_KEY_ELF)
+        .map_err(|e| Error::ProvingFailed(e.to_string()))?;
+    Ok(session.cycles())
 }
 
-pub fn prove_token_validation(token: String, pks: &Vec<String>) -> (Receipt, String) {
+/// Checks the inputs natively and writes them to an env for the guest.
+fn guest_env(
+    token: &str,
+    pks: &[String],
+    options: &ProofOptions,
+) -> Result<ExecutorEnv<'static>, Error> {
+    if !options.revocation_lists.is_empty() && options.revocation_lists.len() != pks.len() {
+        return Err(Error::BadInput(format!(
+            "Got {} revocation lists for {} public keys, expected one per key",
+            options.revocation_lists.len(),
+            pks.len()
+        )));
+    }
+
+    // The guest only accepts the keys sorted by thumbprint without repeats,
+    // and the revocation lists must follow them.
+    let canonical = canonical_key_set(pks)?;
+    let keys: Vec<CompactKey> = canonical.iter().map(|(_, key)| key.clone()).collect();
+    let pks: Vec<String> = canonical
+        .iter()
+        .map(|(index, _)| pks[*index].clone())
+        .collect();
+    let revocation_lists: Vec<&PublishedRevocationList> = if options.revocation_lists.is_empty() {
+        Vec::new()
+    } else {
+        canonical
+            .iter()
+            .map(|(index, _)| &options.revocation_lists[*index])
+            .collect()
+    };
+
+    // The guest only checks the signature against the signing key, so that
+    // its cost does not depend on where that key is in the set.
+    let signer = find_signer(token, &pks).ok_or_else(|| {
+        Error::ProvingFailed("Token is not signed by any of the public keys".to_string())
+    })?;
+
+    for definition in options.statements.buckets.iter() {
+        definition.check()?;
+    }
+
+    let mut statements = options.statements.clone();
+    statements.revocation_lists = revocation_lists
+        .iter()
+        .map(|published| published.list.clone())
+        .collect();
+
+    let non_membership: Option<NonMembershipProof> = revocation_lists
+        .get(signer)
+        .map(|published| published.prove_non_membership(&pks[signer], token))
+        .transpose()?;
+
+    if let Some(delivery_receipt) = &options.delivery_receipt {
+        let (_, invoice) = decode_untrusted_token(token)?;
+        let thumbprints: Vec<String> = keys.iter().map(CompactKey::thumbprint).collect();
+        delivery_receipt
+            .confirm(token, &invoice.custom, &thumbprints)
+            .map_err(|e| Error::ProvingFailed(e.to_string()))?;
+    }
+
+    if let Some(auditor_key) = &statements.auditor_key {
+        let (_, claims) = decode_untrusted_token(token)?;
+        AuditCiphertext::encrypt(&claims.custom, auditor_key, AuditSeed::default())
+            .map_err(|e| Error::BadInput(format!("Invalid auditor key: {e}")))?;
+    }
+
+    let mut env = ExecutorEnv::builder();
+
     // Write the JWT
-    let mut binding = ExecutorEnv::builder();
-    let env = binding.write(&token).expect("failed to write JWT to env");
+    env.write(&token)
+        .map_err(|e| Error::ProvingFailed(format!("Could not write JWT to env: {e}")))?;
 
     // Write the number of public keys
-    env.write(&pks.len())
-        .expect("Could not write number of public keys to env");
+    env.write(&pks.len()).map_err(|e| {
+        Error::ProvingFailed(format!("Could not write number of public keys to env: {e}"))
+    })?;
 
     // Write the public keys
-    for pk in pks.iter() {
-        env.write(pk).expect("failed to write pk to env");
+    for key in keys.iter() {
+        env.write(key)
+            .map_err(|e| Error::ProvingFailed(format!("Could not write pk to env: {e}")))?;
     }
-    let env = env.build().expect("failed to build env");
 
-    let prover = default_prover();
+    // Write the index of the signing key, which stays private
+    env.write(&signer)
+        .map_err(|e| Error::ProvingFailed(format!("Could not write key index to env: {e}")))?;
 
-    let receipt = prover
-        .prove(env, VERIFY_TOKEN_WITH_SOME_KEY_ELF)
-        .expect("failed to prove")
-        .receipt;
+    // Write the statements to prove about the claims
+    env.write(&statements)
+        .map_err(|e| Error::ProvingFailed(format!("Could not write statements to env: {e}")))?;
+
+    // Write the proof that the token is not revoked
+    env.write(&non_membership).map_err(|e| {
+        Error::ProvingFailed(format!("Could not write non-membership proof to env: {e}"))
+    })?;
 
-    /*let output: String = receipt
-    .journal
-    .decode()
-    .expect("Journal should decode to string.");*/
-    let output = "".to_string();
+    // Write the carrier's delivery receipt
+    env.write(&options.delivery_receipt).map_err(|e| {
+        Error::ProvingFailed(format!("Could not write delivery receipt to env: {e}"))
+    })?;
+
+    // Write the randomness for encrypting the claims to the auditor
+    let audit_seed = statements
+        .auditor_key
+        .as_ref()
+        .map(|_| audit::random_seed());
+    env.write(&audit_seed).map_err(|e| {
+        Error::ProvingFailed(format!("Could not write audit randomness to env: {e}"))
+    })?;
+
+    env.build()
+        .map_err(|e| Error::ProvingFailed(format!("Could not build env: {e}")))
+}
 
-    (receipt, output)
+pub fn serialize_receipt(receipt: &Receipt) -> Result<Vec<u8>, Error> {
+    let mut serialized_receipt = Vec::new();
+    receipt
+        .serialize(&mut serialized_receipt)
+        .map_err(|e| Error::io("Could not serialise the receipt", e))?;
+    Ok(serialized_receipt)
+}
+
+pub fn deserialize_receipt(bytes: &[u8]) -> Result<Receipt, Error> {
+    Receipt::try_from_slice(bytes)
+        .map_err(|e| Error::BadInput(format!("Could not deserialise bytes as receipt: {e}")))
+}
+
+/// Checks the receipt against the guest image ID and decodes its journal.
+pub fn verify_receipt(receipt: &Receipt) -> Result<PublicOutput, Error> {
+    receipt
+        .verify(VERIFY_TOKEN_WITH_SOME_KEY_ID)
+        .map_err(|e| Error::VerificationFailed(e.to_string()))?;
+
+    receipt
+        .journal
+        .decode()
+        .map_err(|e| Error::VerificationFailed(format!("Could not decode receipt journal: {e}")))
 }
 
 #[cfg(test)]
 mod test {
-    use methods::VERIFY_TOKEN_WITH_SOME_KEY_ID;
-
     use super::*;
 
     const PK: &str = r#"{
@@ -145,27 +345,27 @@ mod test {
     #[test]
     pub fn test_compute_fingerprint() {
         assert_eq!(
-            compute_fingerprint(PK.to_string()),
+            compute_fingerprint(PK).unwrap(),
             "US_g-NguIHYSNN95ZHMM0_gUI4iM9afv8KPyySaAnUQ".to_string()
         );
     }
 
     #[test]
     pub fn test_prove_token_validation_fails_no_pk() {
-        let token: String = "eyJhbGciOiJSUzI1NiJ9.eyJjbGFpbXMiOlt7ImtleSI6InJlZmVyZW5jZSIsInZhbHVlIjoiNDYxMzYzMjMtNDc3My00OTYwLWE0YTItYTkwNDVkOGIxNjBkIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoiaXNzdWVyX2lkIiwidmFsdWUiOiJDb2ZmZWUgQ2hhaW4gMiIsImlzX3ByaXZhdGUiOnRydWV9LHsia2V5Ijoic3ViamVjdF9pZCIsInZhbHVlIjoiQ29mZmVlIFN1cHBsaWVyIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoicHJvZHVjdCIsInZhbHVlIjoicmF3IGNvZmZlZSBiZWFucyIsImlzX3ByaXZhdGUiOmZhbHNlfSx7ImtleSI6InF1YW50aXR5IiwidmFsdWUiOiIxMDAwIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoiY29zdCIsInZhbHVlIjoiNDAwMCIsImlzX3ByaXZhdGUiOnRydWV9XX0.FYOBvZ1VynCITa4noP8tYEP1dPa3L5XTT6Toxa1nAxU1dGq_VeJ9nW3yk0Ypa69I5KmijrcscVJdqIpbE4DHK5zEzyuT4BxYxZbxijiaKfXkH4kPaCr2iQD3FZX7TQs_HzKtELR8nW1gXegqh_RTudmjZgMH1CK3ic2OzbRsWnRP9oXInStZ6q3EsI1GQvxyXbfKaj5SRtmyfFXIIkQVwWM_cVsAwkkeu-s9r4A_MsEMbUJ210-0_NGxWaqcW2lIPjgLZHoYMdohjM94zkjusgrZas2jhEtif3LwZOgmU8oYldtG-5pMji1bWbiBI0pEx43G5gUm2B-yf9TAP3rbCNkJwo9E_5MeNV5TkWDTPlgHh4fw5-u5Wfk5p9VMRNC3tpmOVvk-PaLQyPmsu8ynYptz8nXNA6HdwLyh5bMGFJgnepxll-iWtn0iWWj-In1_Ht6AUuj9DzGYL-zXfJGykkjyHrus8OE5nREyfCZFKQ-z-ejZRSpufQ2Z14Sdheuc".to_string();
+        let token: String = "eyJhbGciOiJSUzI1NiIsImtpZCI6IjZhYjBlOGU0YmMxMjFmYzI4N2UzNWQzZTVlMGVmYjhhIiwidHlwIjoicG9kK2p3dCJ9.eyJpYXQiOjE3OTIzOTQzMjcsImNsYWltcyI6W3sia2V5IjoicmVmZXJlbmNlIiwidmFsdWUiOiI0NjEzNjMyMy00NzczLTQ5NjAtYTRhMi1hOTA0NWQ4YjE2MGQiLCJpc19wcml2YXRlIjpmYWxzZX0seyJrZXkiOiJpc3N1ZXJfaWQiLCJ2YWx1ZSI6IkNvZmZlZSBDaGFpbiAyIiwiaXNfcHJpdmF0ZSI6dHJ1ZX0seyJrZXkiOiJzdWJqZWN0X2lkIiwidmFsdWUiOiJDb2ZmZWUgU3VwcGxpZXIiLCJpc19wcml2YXRlIjpmYWxzZX0seyJrZXkiOiJwcm9kdWN0IiwidmFsdWUiOiJyYXcgY29mZmVlIGJlYW5zIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoicXVhbnRpdHkiLCJ2YWx1ZSI6IjEwMDAiLCJpc19wcml2YXRlIjpmYWxzZX0seyJrZXkiOiJjb3N0IiwidmFsdWUiOiI0MDAwIiwiaXNfcHJpdmF0ZSI6dHJ1ZX1dLCJsaW5lX2l0ZW1zIjpbXSwibGluZV9pdGVtX2Rpc2Nsb3N1cmUiOnsicHJvZHVjdCI6ZmFsc2UsInF1YW50aXR5IjpmYWxzZSwidW5pdCI6ZmFsc2UsInVuaXRfcHJpY2UiOmZhbHNlLCJ0b3RhbF9xdWFudGl0eSI6ZmFsc2V9fQ.Y3S4skEiWq-ULowD_RWIg6vcjN6fetO0QjvMn_EUJjhb6RYVtg0p1oAPnl8zyoeCrAQCuY0mPrFHzfVycQowno9wljQplRhbPR82rjgjTn0duCiv4dXq-u89JMohi9ycnvIgSQEOP7eI9-EE80XOSFFVGD3uAcKpSJ574jYsazNv5wIOndHA14uOT-RVNpURbyqw0kSWjZj31O-lwIN6LINhlP-pI7PSJLDlIuIWuXO5KyMVJBh6IhLS5gSZAkRr0_MxnD88uDwmyE6sHqtzWFbQlVms-e9YY6vzvXbLqzme_T0xSGC8fl6KknmlWI2OCC056aQX-MmYj20Uz0lIXGYlOAXUfKWGaZAdbgVOxcNsOSYcIedDqmQ3Mmoy3VTtvsfYdXWqkcSz4o3XAlUarjliajLblYzQkkNBkaDO4ErfNgQW-yczs9s1RRKLrfG2zNXXRjSpyBp74W6YGscwY1IYqW2yvw0piVBJ2YEcDXL7TiFLP94YiwbvrXKNLbux".to_string();
         let pks: Vec<String> = [OTHER_PK_1.to_string(), OTHER_PK_2.to_string()].to_vec();
 
-        let (receipt, _) = prove_token_validation(token, &pks);
+        let receipt = prove_token_validation(&token, &pks).unwrap();
         assert!(receipt.verify(VERIFY_TOKEN_WITH_SOME_KEY_ID).is_ok());
     }
 
     #[test]
     #[should_panic]
     pub fn test_prove_token_validation_fails_bad_sig() {
-        let token: String = "eyJhbGciOiJSUzI1NiJ9.eyJjbGFpbXMiOlt7ImtleSI6InJlZmVyZW5jZSIsInZhbHVlIjoiNDYxMzYzMjMtNDc3My00OTYwLWE0YTItYTkwNDVkOGIxNjBkIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoiaXNzdWVyX2lkIiwidmFsdWUiOiJDb2ZmZWUgQ2hhaW4gMiIsImlzX3ByaXZhdGUiOnRydWV9LHsia2V5Ijoic3ViamVjdF9pZCIsInZhbHVlIjoiQ29mZmVlIFN1cHBsaWVyIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoicHJvZHVjdCIsInZhbHVlIjoicmF3IGNvZmZlZSBiZWFucyIsImlzX3ByaXZhdGUiOmZhbHNlfSx7ImtleSI6InF1YW50aXR5IiwidmFsdWUiOiIxMDAwIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoiY29zdCIsInZhbHVlIjoiNDAwMCIsImlzX3ByaXZhdGUiOnRydWV9XX0.FYOBvZ1VynCITa4noP8tYEP1dPa3L5XTT6Toxa1nAxU1dGq_VeJ9nW3yk0Ypa69I5KmijrcscVJdqIpbE4DHK5zEzyuT4BxYxZbxijiaKfXkH4kPaCr2iQD3FZX7TQs_HzKtELR8nW1gXegqh_RTudmjZgMH1CK3ic2OzbRsWnRP9oXInStZ6q3EsI1GQvxyXbfKaj5SRtmyfFXIIkQVwWM_cVsAwkkeu-s9r4A_MsEMbUJ210-0_NGxWaqcW2lIPjgLZHoYMdohjM94zkjusgrZas2jhEtif3LwZOgmU8oYldtG-5pMji1bWbiBI0pEx43G5gUm2B-yf9TAP3rbCNkJwo9E_5MeNV5TkWDUPlgHh4fw5-u5Wfk5p9VMRNC3tpmOVvk-PaLQyPmsu8ynYptz8nXNA6HdwLyh5bMGFJgnepxll-iWtn0iWWj-In1_Ht6AUuj9DzGYL-zXfJGykkjyHrus8OE5nREyfCZFKQ-z-ejZRSpufQ2Z14Sdheuc".to_string();
+        let token: String = "eyJhbGciOiJSUzI1NiIsImtpZCI6IjZhYjBlOGU0YmMxMjFmYzI4N2UzNWQzZTVlMGVmYjhhIiwidHlwIjoicG9kK2p3dCJ9.eyJpYXQiOjE3OTIzOTQzMjcsImNsYWltcyI6W3sia2V5IjoicmVmZXJlbmNlIiwidmFsdWUiOiI0NjEzNjMyMy00NzczLTQ5NjAtYTRhMi1hOTA0NWQ4YjE2MGQiLCJpc19wcml2YXRlIjpmYWxzZX0seyJrZXkiOiJpc3N1ZXJfaWQiLCJ2YWx1ZSI6IkNvZmZlZSBDaGFpbiAyIiwiaXNfcHJpdmF0ZSI6dHJ1ZX0seyJrZXkiOiJzdWJqZWN0X2lkIiwidmFsdWUiOiJDb2ZmZWUgU3VwcGxpZXIiLCJpc19wcml2YXRlIjpmYWxzZX0seyJrZXkiOiJwcm9kdWN0IiwidmFsdWUiOiJyYXcgY29mZmVlIGJlYW5zIiwiaXNfcHJpdmF0ZSI6ZmFsc2V9LHsia2V5IjoicXVhbnRpdHkiLCJ2YWx1ZSI6IjEwMDAiLCJpc19wcml2YXRlIjpmYWxzZX0seyJrZXkiOiJjb3N0IiwidmFsdWUiOiI0MDAwIiwiaXNfcHJpdmF0ZSI6dHJ1ZX1dLCJsaW5lX2l0ZW1zIjpbXSwibGluZV9pdGVtX2Rpc2Nsb3N1cmUiOnsicHJvZHVjdCI6ZmFsc2UsInF1YW50aXR5IjpmYWxzZSwidW5pdCI6ZmFsc2UsInVuaXRfcHJpY2UiOmZhbHNlLCJ0b3RhbF9xdWFudGl0eSI6ZmFsc2V9fQ.Y3S4skEiWq-ULowD_RWIg6vcjN6fetO0QjvMn_EUJjhb6RYVtg0p1oAPnl8zyoeCrAQCuY0mPrFHzfVycQowno9wljQplRhbPR82rjgjTn0duCiv4dXq-u89JMohi9ycnvIgSQEOP7eI9-EE80XOSFFVGD3uAcKpSJ574jYsazNv5wIOndHA14uOT-RVNpURbyqw0kSWjZj31O-lwIN6LINhlP-pI7PSJLDlIuIWuXO5KyMVJBh6IhLS5gSZAkRrA_MxnD88uDwmyE6sHqtzWFbQlVms-e9YY6vzvXbLqzme_T0xSGC8fl6KknmlWI2OCC056aQX-MmYj20Uz0lIXGYlOAXUfKWGaZAdbgVOxcNsOSYcIedDqmQ3Mmoy3VTtvsfYdXWqkcSz4o3XAlUarjliajLblYzQkkNBkaDO4ErfNgQW-yczs9s1RRKLrfG2zNXXRjSpyBp74W6YGscwY1IYqW2yvw0piVBJ2YEcDXL7TiFLP94YiwbvrXKNLbux".to_string();
         let pks: Vec<String> = [COFFEE_COMPANY_PK.to_string(), OTHER_PK_1.to_string()].to_vec();
 
-        let (receipt, _) = prove_token_validation(token, &pks);
+        let receipt = prove_token_validation(&token, &pks).unwrap();
         assert!(receipt.verify(VERIFY_TOKEN_WITH_SOME_KEY_ID).is_ok());
     }
 }
@@ -0,0 +1,633 @@
+//! Machine-readable summary of verifying a Zero-Knowledge Proof of Delivery.
+
+use crate::revocation::PublishedRevocationList;
+use crate::{compute_fingerprint, deserialize_receipt, verify_receipt, Error};
+use jwt_core::{DateWindow, JwkMetadata, KeyValidity, UnitPriceBand, Validator};
+use methods::VERIFY_TOKEN_WITH_SOME_KEY_ID;
+use risc0_zkvm::{Digest, InnerReceipt, Receipt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Verified,
+    Failed,
+    InvalidInput,
+}
+
+/// A claim that is only disclosed as lying in a range.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ClaimRange {
+    pub claim: String,
+    pub label: String,
+    pub min: u64,
+    pub max: Option<u64>,
+}
+
+/// Root and version of a candidate key's revocation list committed by the
+/// receipt.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyRevocation {
+    pub key_fingerprint: String,
+    pub root: String,
+    pub version: u64,
+    /// Whether the root was compared with the issuer's current list.  When
+    /// it was not, the receipt only shows that the token was absent from
+    /// some version of the list, which may since have been replaced.
+    pub checked: bool,
+}
+
+/// The carrier's confirmation that the goods were delivered.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeliveryReport {
+    pub key_fingerprints: Vec<String>,
+    pub public_claims: Map<String, Value>,
+}
+
+/// Claims encrypted to an auditor, who can recover them with `audit-decrypt`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport {
+    pub key_fingerprint: String,
+    pub blocks: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerificationReport {
+    pub status: VerificationStatus,
+    pub image_id: String,
+    pub receipt_kind: Option<String>,
+    /// Size of the serialised receipt in bytes, when verified from bytes.
+    pub receipt_size: Option<usize>,
+    pub key_fingerprints: Vec<String>,
+    /// Number of distinct keys the signing key is hidden among; the guest
+    /// rejects repeated keys.
+    pub distinct_keys: usize,
+    /// Window in which each candidate key may have signed the token.
+    pub key_validity: Vec<KeyValidity>,
+    /// The `use`, `key_ops`, `alg` and `kid` of each candidate key, which
+    /// the guest checked and committed.
+    pub key_metadata: Vec<JwkMetadata>,
+    /// Whether the windows were compared with a trusted key set.  The
+    /// prover chooses the windows it proves against, so until they are,
+    /// they only state what the prover claims.
+    pub key_validity_checked: bool,
+    pub public_claims: Map<String, Value>,
+    /// Number of private claims the token holds besides the public ones.
+    pub hidden_claims: usize,
+    /// Number of line item fields left out of the disclosed lines.
+    pub hidden_line_item_fields: usize,
+    pub claim_ranges: Vec<ClaimRange>,
+    pub unit_price_bands: Vec<UnitPriceBand>,
+    pub date_windows: Vec<DateWindow>,
+    pub revocation: Vec<KeyRevocation>,
+    pub delivery: Option<DeliveryReport>,
+    pub audit: Option<AuditReport>,
+    pub failure_reasons: Vec<String>,
+    /// Candidate keys from the journal, to check revocation lists against.
+    #[serde(skip)]
+    public_keys: Vec<String>,
+}
+
+impl VerificationReport {
+    fn new(status: VerificationStatus) -> Self {
+        VerificationReport {
+            status,
+            image_id: Digest::from(VERIFY_TOKEN_WITH_SOME_KEY_ID).to_string(),
+            receipt_kind: None,
+            receipt_size: None,
+            key_fingerprints: Vec::new(),
+            distinct_keys: 0,
+            key_validity: Vec::new(),
+            key_metadata: Vec::new(),
+            key_validity_checked: false,
+            public_claims: Map::new(),
+            hidden_claims: 0,
+            hidden_line_item_fields: 0,
+            claim_ranges: Vec::new(),
+            unit_price_bands: Vec::new(),
+            date_windows: Vec::new(),
+            revocation: Vec::new(),
+            delivery: None,
+            audit: None,
+            failure_reasons: Vec::new(),
+            public_keys: Vec::new(),
+        }
+    }
+
+    pub fn from_receipt_bytes(bytes: &[u8]) -> Self {
+        let mut report = match deserialize_receipt(bytes) {
+            Ok(receipt) => Self::from_receipt(&receipt),
+            Err(e) => {
+                let mut report = Self::new(VerificationStatus::InvalidInput);
+                report.failure_reasons.push(e.to_string());
+                report
+            }
+        };
+        report.receipt_size = Some(bytes.len());
+        report
+    }
+
+    pub fn from_receipt(receipt: &Receipt) -> Self {
+        let mut report = Self::new(VerificationStatus::Verified);
+        report.receipt_kind = Some(receipt_kind(receipt).to_string());
+
+        let public_outputs = match verify_receipt(receipt) {
+            Ok(public_outputs) => public_outputs,
+            Err(e) => {
+                report.status = VerificationStatus::Failed;
+                report.failure_reasons.push(e.to_string());
+                return report;
+            }
+        };
+
+        for (index, pk) in public_outputs.pks.iter().enumerate() {
+            match compute_fingerprint(pk) {
+                Ok(fingerprint) => report.key_fingerprints.push(fingerprint),
+                Err(e) => {
+                    report.status = VerificationStatus::Failed;
+                    report.failure_reasons.push(format!(
+                        "Key {} could not be fingerprinted: {}",
+                        index + 1,
+                        e
+                    ));
+                }
+            }
+        }
+        report.distinct_keys = public_outputs.pks.len();
+        report.key_validity = public_outputs
+            .pks
+            .iter()
+            .map(|pk| KeyValidity::from_jwk(pk).unwrap_or_default())
+            .collect();
+        report.key_metadata = public_outputs
+            .pks
+            .iter()
+            .map(|pk| JwkMetadata::from_jwk(pk).unwrap_or_default())
+            .collect();
+        report.public_claims = public_outputs.claims.to_json_object();
+        report.hidden_claims = public_outputs.hidden_claims;
+        report.hidden_line_item_fields = public_outputs.hidden_line_item_fields;
+        report.public_keys = public_outputs.pks.clone();
+
+        report.unit_price_bands = public_outputs.unit_price_bands.clone();
+        report.date_windows = public_outputs.date_windows.clone();
+
+        report.claim_ranges = public_outputs
+            .buckets
+            .iter()
+            .map(|disclosure| ClaimRange {
+                claim: disclosure.definition.claim.clone(),
+                label: disclosure.bucket.label.clone(),
+                min: disclosure.bucket.min,
+                max: disclosure.bucket.max,
+            })
+            .collect();
+
+        if let Some(delivery) = &public_outputs.delivery {
+            let mut key_fingerprints = Vec::new();
+            for (index, pk) in delivery.pks.iter().enumerate() {
+                match compute_fingerprint(pk) {
+                    Ok(fingerprint) => key_fingerprints.push(fingerprint),
+                    Err(e) => {
+                        report.status = VerificationStatus::Failed;
+                        report.failure_reasons.push(format!(
+                            "Carrier key {} could not be fingerprinted: {}",
+                            index + 1,
+                            e
+                        ));
+                    }
+                }
+            }
+            report.delivery = Some(DeliveryReport {
+                key_fingerprints,
+                public_claims: delivery.claims.to_json_object(),
+            });
+        }
+
+        report.audit = public_outputs.audit.as_ref().map(|audit| AuditReport {
+            key_fingerprint: audit.key_fingerprint.clone(),
+            blocks: audit.blocks.len(),
+        });
+
+        for (index, root) in public_outputs.revocation.iter().enumerate() {
+            report.revocation.push(KeyRevocation {
+                key_fingerprint: report
+                    .key_fingerprints
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_default(),
+                root: root.root.clone(),
+                version: root.version,
+                checked: false,
+            });
+        }
+
+        report
+    }
+
+    /// Checks that the receipt proves non-revocation against the `current`
+    /// revocation list of each of the given issuers.  Each list must be
+    /// signed by one of the candidate keys.
+    pub fn check_revocation_lists(&mut self, current: &[PublishedRevocationList]) {
+        if !self.is_verified() {
+            return;
+        }
+        for (number, published) in current.iter().enumerate() {
+            let signed_by = self.public_keys.iter().enumerate().find_map(|(index, pk)| {
+                let claims = pk
+                    .parse::<Validator>()
+                    .ok()?
+                    .validate_revocation_list(&published.list)
+                    .ok()?;
+                Some((index, claims))
+            });
+            let reason = match &signed_by {
+                None => Some(format!(
+                    "Revocation list {} is not signed by any of the candidate keys",
+                    number + 1
+                )),
+                Some((index, claims)) => match self.revocation.get_mut(*index) {
+                    None => Some(format!(
+                        "The receipt does not prove that the token is absent from the revocation list of key {}",
+                        index + 1
+                    )),
+                    Some(committed)
+                        if committed.root != claims.root || committed.version != claims.version =>
+                    {
+                        Some(format!(
+                            "Revocation list of key {} is out of date: the receipt proves version {}, the current version is {}",
+                            index + 1,
+                            committed.version,
+                            claims.version
+                        ))
+                    }
+                    Some(committed) => {
+                        committed.checked = true;
+                        None
+                    }
+                },
+            };
+            if let Some(reason) = reason {
+                self.status = VerificationStatus::Failed;
+                self.failure_reasons.push(reason);
+            }
+        }
+    }
+
+    /// Checks that every candidate key is in the `trusted` key set, with the
+    /// validity window given there, such as the windows `zkpod keys chain`
+    /// derives from the issuer's rotation records.
+    pub fn check_key_validity(&mut self, trusted: &[String]) {
+        if !self.is_verified() {
+            return;
+        }
+        let mut reasons = Vec::new();
+        for (fingerprint, validity) in self.key_fingerprints.iter().zip(self.key_validity.iter()) {
+            let trusted_validity = trusted.iter().find_map(|key| {
+                (compute_fingerprint(key).ok()? == *fingerprint)
+                    .then(|| KeyValidity::from_jwk(key).ok())?
+            });
+            match trusted_validity {
+                None => reasons.push(format!("Key {fingerprint} is not in the trusted key set")),
+                Some(trusted_validity) if trusted_validity != *validity => reasons.push(format!(
+                    "Key {fingerprint} is {trusted_validity} in the trusted key set, but the receipt was proved with it {validity}"
+                )),
+                Some(_) => {}
+            }
+        }
+        if reasons.is_empty() {
+            self.key_validity_checked = true;
+        } else {
+            self.status = VerificationStatus::Failed;
+            self.failure_reasons.extend(reasons);
+        }
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.status == VerificationStatus::Verified
+    }
+
+    /// Turns a report that is not verified into the matching error, so that
+    /// callers can exit with the right code.
+    pub fn into_result(self) -> Result<Self, Error> {
+        let reasons = self.failure_reasons.join("; ");
+        match self.status {
+            VerificationStatus::Verified => Ok(self),
+            VerificationStatus::Failed => Err(Error::VerificationFailed(reasons)),
+            VerificationStatus::InvalidInput => Err(Error::BadInput(reasons)),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        if !self.is_verified() {
+            let mut text = "Verification failed!\n".to_string();
+            for reason in self.failure_reasons.iter() {
+                text += &format!("\n{}", reason);
+            }
+            return text;
+        }
+
+        let mut text = "Verification succeeded!\n".to_string();
+        text += &format!(
+            "\nThe prover has a JWT signed by the secret key corresponding to one of the following {} distinct public keys:",
+            self.distinct_keys
+        );
+        for ((fingerprint, validity), metadata) in self
+            .key_fingerprints
+            .iter()
+            .zip(self.key_validity.iter())
+            .zip(self.key_metadata.iter())
+        {
+            text += &format!("\n  {}", fingerprint);
+            if !validity.is_unbounded() {
+                text += &format!(" ({})", validity);
+            }
+            if !metadata.is_empty() {
+                text += &format!(" [{}]", metadata);
+            }
+        }
+        if self.key_validity_checked {
+            text += "\n\nThe keys and their validity windows match the trusted key set.";
+        } else {
+            text += "\n\nThe validity windows of the keys are as asserted by the prover; pass the issuer's keys with --key-set (and rotation records with --rotation) to check them.";
+        }
+        let line_item_fields = if self.hidden_line_item_fields > 0 {
+            format!(
+                " and {} undisclosed line item fields",
+                self.hidden_line_item_fields
+            )
+        } else {
+            String::new()
+        };
+        text += &format!(
+            "\n\nThe JWT attests to the following public claims (and {} undisclosed private claims{}):\n",
+            self.hidden_claims, line_item_fields
+        );
+        text += &serde_json::to_string_pretty(&self.public_claims)
+            .expect("a JSON map always serialises");
+        if !self.claim_ranges.is_empty() {
+            text += "\n\nThe JWT attests that the following claims lie in these ranges:";
+            for range in self.claim_ranges.iter() {
+                let bounds = match range.max {
+                    Some(max) => format!("{} to {}", range.min, max),
+                    None => format!("{} or more", range.min),
+                };
+                text += &format!("\n  {}: {} ({})", range.claim, bounds, range.label);
+            }
+        }
+        if !self.unit_price_bands.is_empty() {
+            text += "\n\nThe JWT attests that the following unit prices lie in these bands:";
+            for band in self.unit_price_bands.iter() {
+                text += &format!(
+                    "\n  {} / {}: {} to {}",
+                    band.cost_claim, band.quantity_claim, band.min, band.max
+                );
+            }
+        }
+        if !self.date_windows.is_empty() {
+            text += "\n\nThe JWT attests that the following dates lie in these windows:";
+            for window in self.date_windows.iter() {
+                text += &format!("\n  {}: {} to {}", window.claim, window.from, window.until);
+            }
+        }
+        if let Some(delivery) = &self.delivery {
+            text += "\n\nDelivery is confirmed by a delivery receipt with the same reference and the status \"delivered\", signed by the secret key corresponding to one of the following carrier keys, none of which is a candidate key of the invoice:";
+            for fingerprint in delivery.key_fingerprints.iter() {
+                text += &format!("\n  {}", fingerprint);
+            }
+            text += "\n\nThe delivery receipt attests to the following public claims:\n";
+            text += &serde_json::to_string_pretty(&delivery.public_claims)
+                .expect("a JSON map always serialises");
+        }
+        if let Some(audit) = &self.audit {
+            text += &format!(
+                "\n\nAll of the claims are encrypted to the auditor key {}",
+                audit.key_fingerprint
+            );
+        }
+        if !self.revocation.is_empty() {
+            text += "\n\nThe JWT is not on the following revocation lists:";
+            for revocation in self.revocation.iter() {
+                text += &format!(
+                    "\n  {}: version {} (root {})",
+                    revocation.key_fingerprint, revocation.version, revocation.root
+                );
+                if !revocation.checked {
+                    text += ", not checked against the current list";
+                }
+            }
+            if self.revocation.iter().any(|revocation| !revocation.checked) {
+                text += "\n\nRevocation was not checked for the lists marked above: pass their current versions with --revocation-list, or the token may have been revoked since it was proved.";
+            }
+        }
+        text
+    }
+}
+
+fn receipt_kind(receipt: &Receipt) -> &'static str {
+    match receipt.inner {
+        InnerReceipt::Composite(_) => "composite",
+        InnerReceipt::Succinct(_) => "succinct",
+        InnerReceipt::Groth16(_) => "groth16",
+        InnerReceipt::Fake(_) => "fake",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use jwt_core::{
+        BucketDefinition, CustomClaims, DeliveryConfirmation, PublicOutput, RevocationRoot,
+        Statements, UnitPriceBand,
+    };
+    use risc0_zkvm::{FakeReceipt, ReceiptClaim};
+
+    pub(crate) const PK: &str = r#"{
+    "alg": "RS256",
+    "e": "AQAB",
+    "kty": "RSA",
+    "n": "zcQwXx3EevOSkfH0VSWqtfmWTL4c2oIzW6u83qKO1W7XjLgTqpryL5vNCaxbVTkpU-GZctit0n6kj570tfny_sy6pb2q9wlvFBmDVyD-nL5oNjP5s3qEfvy15Bl9vMGFf3zycqMaVg_7VRVwK5d8QzpnVC0AGT10QdHnyGCadfPJqazTuVRp1f3ecK7bg7596sgVb8d9Wpaz2XPykQPfphsEb40vcp1tPN95-eRCgA24PwfUaKYHQQFMEQY_atJWbffyJ91zsBRy8fEQdfuQVZIRVQgO7FTsmLmQAHxR1dl2jP8B6zonWmtqWoMHoZfa-kmTPB4wNHa8EaLvtQ1060qYFmQWWumfNFnG7HNq2gTHt1cN1HCwstRGIaU_ZHubM_FKH_gLfJPKNW0KWML9mQQzf4AVov0Yfvk89WxY8ilSRx6KodJuIKKqwVh_58PJPLmBqszEfkTjtyxPwP8X8xRXfSz-vTU6vESCk3O6TRknoJkC2BJZ_ONQ0U5dxLcx"
+}"#;
+
+    /// A dev-mode receipt committing to `output`, which only verifies when
+    /// RISC0_DEV_MODE is set (as it is in CI).
+    pub(crate) fn fake_receipt(output: &PublicOutput) -> Receipt {
+        let journal: Vec<u8> = risc0_zkvm::serde::to_vec(output)
+            .unwrap()
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        let claim = ReceiptClaim::ok(VERIFY_TOKEN_WITH_SOME_KEY_ID, journal.clone());
+        Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal)
+    }
+
+    #[test]
+    fn test_report_for_invalid_receipt() {
+        let report = VerificationReport::from_receipt_bytes(b"not a receipt");
+
+        assert_eq!(report.status, VerificationStatus::InvalidInput);
+        assert_eq!(report.receipt_size, Some(13));
+        assert_eq!(report.failure_reasons.len(), 1);
+        assert!(report.to_text().starts_with("Verification failed!"));
+        assert!(matches!(report.into_result(), Err(Error::BadInput(_))));
+    }
+
+    #[test]
+    fn test_report_for_verified_receipt() {
+        let mut claims = CustomClaims::new();
+        claims.add("quantity".to_string(), "1000".to_string(), false);
+        claims.add("cost".to_string(), "4000.00 GBP".to_string(), true);
+        claims.add("delivery_date".to_string(), "2026-03-14".to_string(), true);
+        let statements = Statements {
+            buckets: vec!["quantity:1000,5000".parse::<BucketDefinition>().unwrap()],
+            unit_price_bands: vec!["cost/quantity=3.50 GBP..4.50 GBP"
+                .parse::<UnitPriceBand>()
+                .unwrap()],
+            date_windows: vec!["delivery_date=2025-04-01..2026-03-31"
+                .parse::<DateWindow>()
+                .unwrap()],
+            ..Statements::default()
+        };
+        let mut output = PublicOutput::new(vec![PK.to_string()], &claims, &statements).unwrap();
+        let mut delivery_claims = CustomClaims::new();
+        delivery_claims.add("status".to_string(), "delivered".to_string(), false);
+        output.delivery = Some(DeliveryConfirmation {
+            pks: vec![PK.to_string()],
+            claims: delivery_claims,
+        });
+
+        let report = VerificationReport::from_receipt(&fake_receipt(&output));
+
+        assert_eq!(report.status, VerificationStatus::Verified);
+        assert_eq!(report.receipt_kind.as_deref(), Some("fake"));
+        assert_eq!(report.distinct_keys, 1);
+        assert!(report
+            .to_text()
+            .contains("one of the following 1 distinct public keys"));
+        assert_eq!(
+            report.key_fingerprints,
+            vec!["US_g-NguIHYSNN95ZHMM0_gUI4iM9afv8KPyySaAnUQ".to_string()]
+        );
+        assert_eq!(report.key_metadata[0].alg.as_deref(), Some("RS256"));
+        assert!(report.to_text().contains("[alg RS256]"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["status"], "verified");
+        assert_eq!(json["public_claims"]["quantity"], "1000");
+        assert!(json["public_claims"].get("cost").is_none());
+        assert_eq!(json["hidden_claims"], 2);
+        assert!(report
+            .to_text()
+            .contains("(and 2 undisclosed private claims)"));
+        assert_eq!(json["claim_ranges"][0]["label"], "1000-4999");
+        assert!(report.to_text().contains("quantity: 1000 to 4999"));
+        assert!(report
+            .to_text()
+            .contains("cost / quantity: 3.50 GBP to 4.50 GBP"));
+        assert_eq!(json["unit_price_bands"][0]["min"], "3.50 GBP");
+        assert_eq!(json["delivery"]["public_claims"]["status"], "delivered");
+        assert!(json["public_claims"].get("delivery_date").is_none());
+        assert!(report
+            .to_text()
+            .contains("delivery_date: 2025-04-01 to 2026-03-31"));
+        assert!(report.to_text().contains("Delivery is confirmed"));
+    }
+
+    #[test]
+    fn test_report_checks_key_validity_against_key_set() {
+        let windowed = crate::key_set::with_validity(
+            PK,
+            &KeyValidity {
+                not_before: None,
+                not_after: Some(1000),
+            },
+        )
+        .unwrap();
+        let output = PublicOutput::new(
+            vec![windowed.clone()],
+            &CustomClaims::new(),
+            &Statements::default(),
+        )
+        .unwrap();
+        let receipt = fake_receipt(&output);
+
+        let report = VerificationReport::from_receipt(&receipt);
+        assert!(!report.key_validity_checked);
+        assert!(report.to_text().contains("as asserted by the prover"));
+
+        let mut report = VerificationReport::from_receipt(&receipt);
+        report.check_key_validity(&[windowed]);
+        assert!(report.is_verified());
+        assert!(report.key_validity_checked);
+        assert!(report.to_text().contains("match the trusted key set"));
+
+        // The prover dropped the end of the key's window.
+        let mut report = VerificationReport::from_receipt(&fake_receipt(
+            &PublicOutput::new(
+                vec![PK.to_string()],
+                &CustomClaims::new(),
+                &Statements::default(),
+            )
+            .unwrap(),
+        ));
+        report.check_key_validity(&[crate::key_set::with_validity(
+            PK,
+            &KeyValidity {
+                not_before: None,
+                not_after: Some(1000),
+            },
+        )
+        .unwrap()]);
+        assert_eq!(report.status, VerificationStatus::Failed);
+        assert!(report.failure_reasons[0].contains("in the trusted key set"));
+
+        let mut report = VerificationReport::from_receipt(&receipt);
+        report.check_key_validity(&[]);
+        assert_eq!(report.status, VerificationStatus::Failed);
+        assert!(report.failure_reasons[0].contains("is not in the trusted key set"));
+    }
+
+    #[test]
+    fn test_report_checks_revocation_lists_are_current() {
+        const SK: &str = include_str!("../../test_data/coffee_company_sk.jwk");
+        const ISSUER_PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+        let proved = PublishedRevocationList::publish(SK, 1, &[]).unwrap();
+        let claims = ISSUER_PK
+            .parse::<Validator>()
+            .unwrap()
+            .validate_revocation_list(&proved.list)
+            .unwrap();
+
+        let mut output = PublicOutput::new(
+            vec![ISSUER_PK.to_string()],
+            &CustomClaims::new(),
+            &Statements::default(),
+        )
+        .unwrap();
+        output.revocation = vec![RevocationRoot {
+            root: claims.root,
+            version: claims.version,
+        }];
+        let receipt = fake_receipt(&output);
+
+        let mut report = VerificationReport::from_receipt(&receipt);
+        assert!(!report.revocation[0].checked);
+        assert!(report
+            .to_text()
+            .contains("not checked against the current list"));
+        report.check_revocation_lists(std::slice::from_ref(&proved));
+        assert!(report.is_verified());
+        assert_eq!(report.revocation[0].version, 1);
+        assert!(report.revocation[0].checked);
+        assert!(report.to_text().contains("version 1"));
+        assert!(!report.to_text().contains("not checked"));
+
+        let newer = PublishedRevocationList::publish(SK, 2, &[[7; 32]]).unwrap();
+        let mut report = VerificationReport::from_receipt(&receipt);
+        report.check_revocation_lists(&[newer]);
+        assert_eq!(report.status, VerificationStatus::Failed);
+        assert!(report.failure_reasons[0].contains("out of date"));
+    }
+}
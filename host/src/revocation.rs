@@ -0,0 +1,124 @@
+//! Revocation lists as published by issuers: the signed root together with
+//! the revoked token hashes it commits to.
+
+use crate::Error;
+use jwt_core::revocation::{hash_from_hex, hash_to_hex, Hash};
+use jwt_core::{
+    jwk_thumbprint, token_hash, Issuer, NonMembershipProof, RevocationTree, ThumbprintHash,
+    Validator,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PublishedRevocationList {
+    /// Signed revocation list, see [`jwt_core::RevocationListClaims`].
+    pub list: String,
+    /// Hex encoded SHA-256 hashes of the revoked tokens.
+    pub revoked: Vec<String>,
+}
+
+impl PublishedRevocationList {
+    /// Signs a new version of the issuer's revocation list.  `secret_key` is
+    /// the issuer's JWK.
+    pub fn publish(secret_key: &str, version: u64, revoked: &[Hash]) -> Result<Self, Error> {
+        let issuer = secret_key.parse::<Issuer>()?;
+        let tree = RevocationTree::new(revoked);
+        let claims = tree.claims(jwk_thumbprint(secret_key, ThumbprintHash::Sha256)?, version);
+
+        let mut revoked: Vec<String> = revoked.iter().map(hash_to_hex).collect();
+        revoked.sort();
+        revoked.dedup();
+
+        Ok(PublishedRevocationList {
+            list: issuer.sign_revocation_list(&claims)?,
+            revoked,
+        })
+    }
+
+    /// Checks the list against the issuer's key and proves that `token` is
+    /// not on it.
+    pub fn prove_non_membership(&self, pk: &str, token: &str) -> Result<NonMembershipProof, Error> {
+        let claims = pk
+            .parse::<Validator>()?
+            .validate_revocation_list(&self.list)?;
+        let revoked = self
+            .revoked
+            .iter()
+            .map(|hash| hash_from_hex(hash))
+            .collect::<Result<Vec<Hash>, jwt_core::Err>>()?;
+
+        let tree = RevocationTree::new(&revoked);
+        if hash_to_hex(&tree.root()) != claims.root {
+            return Err(Error::BadInput(
+                "Revoked hashes do not match the signed revocation list".to_string(),
+            ));
+        }
+
+        tree.prove_non_membership(&token_hash(token))
+            .map_err(|e| Error::ProvingFailed(e.to_string()))
+    }
+}
+
+/// Parses one entry of a list of revoked tokens: either a token, which is
+/// hashed, or the hex encoded hash of one.
+pub fn parse_revoked_entry(entry: &str) -> Result<Hash, Error> {
+    let entry = entry.trim();
+    if entry.contains('.') {
+        Ok(token_hash(entry))
+    } else {
+        Ok(hash_from_hex(entry)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jwt_core::{check_revocation, CustomClaims};
+
+    const SK: &str = include_str!("../../test_data/coffee_company_sk.jwk");
+    const PK: &str = include_str!("../../test_data/coffee_company_pk.jwk");
+
+    #[test]
+    fn test_publish_and_prove_non_membership() {
+        let issuer = SK.parse::<Issuer>().unwrap();
+        let mut claims = CustomClaims::new();
+        claims.add("reference".to_string(), "1".to_string(), false);
+        let token = issuer.generate_token(&claims).unwrap();
+        claims.add("reference".to_string(), "2".to_string(), false);
+        let revoked_token = issuer.generate_token(&claims).unwrap();
+
+        let published = PublishedRevocationList::publish(
+            SK,
+            3,
+            &[parse_revoked_entry(&revoked_token).unwrap()],
+        )
+        .unwrap();
+        assert_eq!(
+            published.revoked,
+            vec![hash_to_hex(&token_hash(&revoked_token))]
+        );
+
+        let proof = published.prove_non_membership(PK, &token).unwrap();
+        let roots = check_revocation(
+            &token,
+            &[PK.to_string()],
+            0,
+            std::slice::from_ref(&published.list),
+            Some(&proof),
+        )
+        .unwrap();
+        assert_eq!(roots[0].version, 3);
+
+        assert!(matches!(
+            published.prove_non_membership(PK, &revoked_token),
+            Err(Error::ProvingFailed(_))
+        ));
+
+        let mut tampered = published.clone();
+        tampered.revoked.clear();
+        assert!(matches!(
+            tampered.prove_non_membership(PK, &revoked_token),
+            Err(Error::BadInput(_))
+        ));
+    }
+}
@@ -0,0 +1,526 @@
+//! Local HTTP service for proving and verifying Zero-Knowledge Proofs of
+//! Delivery, so that other applications do not have to run the command line
+//! tools as subprocesses.
+//!
+//! Proving jobs are queued and run on a fixed number of workers. Each job is
+//! persisted as JSON in the state directory, together with its receipt once
+//! proved, so that queued jobs survive a restart. The persisted job includes
+//! the token, so the state directory must be protected like the token itself.
+
+use crate::key_set::apply_rotations;
+use crate::report::VerificationReport;
+use crate::revocation::PublishedRevocationList;
+use crate::{prove_token_validation_with, serialize_receipt, Error, ProofOptions};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::prelude::*;
+use jwt_core::{DeliveryReceipt, Statements};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A proving job as persisted in the state directory.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobRecord {
+    pub id: String,
+    pub status: JobStatus,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+    pub error: Option<String>,
+    token: String,
+    public_keys: Vec<String>,
+    #[serde(default, flatten)]
+    options: ProofOptions,
+}
+
+impl JobRecord {
+    pub fn new(token: String, public_keys: Vec<String>, options: ProofOptions) -> Self {
+        JobRecord {
+            id: Uuid::new_v4().to_string(),
+            status: JobStatus::Queued,
+            created_at: unix_time(),
+            finished_at: None,
+            error: None,
+            token,
+            public_keys,
+            options,
+        }
+    }
+}
+
+/// A proving job as returned by the API, which never includes the token.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobResponse {
+    pub id: String,
+    pub status: JobStatus,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+    pub error: Option<String>,
+    /// Base64 encoded receipt, as written by `zkpod prove`, once succeeded.
+    pub receipt: Option<String>,
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub struct JobStore {
+    dir: PathBuf,
+    jobs: Mutex<HashMap<String, JobRecord>>,
+}
+
+impl JobStore {
+    /// Opens the store, loading any persisted jobs. Jobs that were running
+    /// when the service stopped are queued again.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, Error> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| Error::io(format!("Could not create {}", dir.display()), e))?;
+
+        let mut jobs = HashMap::new();
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| Error::io(format!("Could not read {}", dir.display()), e))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|e| Error::io(format!("Could not read {}", dir.display()), e))?
+                .path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let bytes = std::fs::read(&path)
+                    .map_err(|e| Error::io(format!("Could not read {}", path.display()), e))?;
+                let mut job: JobRecord = serde_json::from_slice(&bytes)?;
+                if job.status == JobStatus::Running {
+                    job.status = JobStatus::Queued;
+                }
+                jobs.insert(job.id.clone(), job);
+            }
+        }
+
+        Ok(JobStore {
+            dir,
+            jobs: Mutex::new(jobs),
+        })
+    }
+
+    fn job_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    pub fn receipt_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.bin"))
+    }
+
+    fn persist(&self, job: &JobRecord) -> Result<(), Error> {
+        let path = self.job_path(&job.id);
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(job)?)
+            .and_then(|_| std::fs::rename(&tmp_path, &path))
+            .map_err(|e| Error::io(format!("Could not write {}", path.display()), e))
+    }
+
+    pub fn insert(&self, job: JobRecord) -> Result<(), Error> {
+        self.persist(&job)?;
+        self.jobs.lock().unwrap().insert(job.id.clone(), job);
+        Ok(())
+    }
+
+    pub fn update(&self, id: &str, f: impl FnOnce(&mut JobRecord)) -> Result<(), Error> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs
+            .get_mut(id)
+            .ok_or_else(|| Error::BadInput(format!("Unknown job {id}")))?;
+        f(job);
+        self.persist(job)
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.jobs.lock().unwrap().remove(id);
+        let _ = std::fs::remove_file(self.job_path(id));
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobRecord> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    /// IDs of queued jobs, oldest first.
+    pub fn queued(&self) -> Vec<String> {
+        let jobs = self.jobs.lock().unwrap();
+        let mut queued: Vec<&JobRecord> = jobs
+            .values()
+            .filter(|job| job.status == JobStatus::Queued)
+            .collect();
+        queued.sort_by_key(|job| job.created_at);
+        queued.into_iter().map(|job| job.id.clone()).collect()
+    }
+
+    pub fn response(&self, id: &str) -> Option<JobResponse> {
+        let job = self.get(id)?;
+        let receipt = match job.status {
+            JobStatus::Succeeded => std::fs::read(self.receipt_path(id))
+                .ok()
+                .map(|bytes| BASE64_STANDARD.encode(bytes)),
+            _ => None,
+        };
+        Some(JobResponse {
+            id: job.id,
+            status: job.status,
+            created_at: job.created_at,
+            finished_at: job.finished_at,
+            error: job.error,
+            receipt,
+        })
+    }
+}
+
+pub struct ServerConfig {
+    pub state_dir: PathBuf,
+    pub workers: usize,
+    pub queue_size: usize,
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    store: Arc<JobStore>,
+    queue: mpsc::Sender<String>,
+}
+
+/// Opens the job store, starts the proving workers and returns the router.
+/// Must be called from within a tokio runtime.
+pub fn start(config: ServerConfig) -> Result<Router, Error> {
+    let store = Arc::new(JobStore::open(&config.state_dir)?);
+    let queued = store.queued();
+
+    let (sender, receiver) = mpsc::channel(config.queue_size.max(queued.len()).max(1));
+    for id in queued {
+        sender
+            .try_send(id)
+            .expect("queue is large enough for the persisted jobs");
+    }
+
+    let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+    for _ in 0..config.workers.max(1) {
+        tokio::spawn(worker(store.clone(), receiver.clone()));
+    }
+
+    Ok(router(AppState {
+        store,
+        queue: sender,
+    }))
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/proofs", post(create_proof))
+        .route("/proofs/{id}", get(get_proof))
+        .route("/verify", post(verify))
+        .with_state(state)
+}
+
+async fn worker(store: Arc<JobStore>, queue: Arc<tokio::sync::Mutex<mpsc::Receiver<String>>>) {
+    loop {
+        let next = queue.lock().await.recv().await;
+        match next {
+            Some(id) => run_job(&store, id).await,
+            None => break,
+        }
+    }
+}
+
+async fn run_job(store: &Arc<JobStore>, id: String) {
+    let Some(job) = store.get(&id) else {
+        return;
+    };
+    if let Err(e) = store.update(&id, |job| job.status = JobStatus::Running) {
+        tracing::error!("Could not start job {id}: {e}");
+        return;
+    }
+
+    let receipt_path = store.receipt_path(&id);
+    let result = tokio::task::spawn_blocking(move || {
+        let receipt = prove_token_validation_with(&job.token, &job.public_keys, &job.options)?;
+        std::fs::write(&receipt_path, serialize_receipt(&receipt)?)
+            .map_err(|e| Error::io(format!("Could not write {}", receipt_path.display()), e))
+    })
+    .await
+    .unwrap_or_else(|e| Err(Error::ProvingFailed(format!("Proving task failed: {e}"))));
+
+    let update = store.update(&id, |job| {
+        job.finished_at = Some(unix_time());
+        match result {
+            Ok(()) => job.status = JobStatus::Succeeded,
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e.to_string());
+            }
+        }
+    });
+    if let Err(e) = update {
+        tracing::error!("Could not record result of job {id}: {e}");
+    }
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "error": message.into() }))).into_response()
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProofRequest {
+    pub token: String,
+    /// Candidate verification keys, each a JWK object or a JWK as a string.
+    pub public_keys: Vec<Value>,
+    /// Statements to prove about the claims, e.g. bucket definitions.
+    #[serde(default)]
+    pub statements: Statements,
+    /// Published revocation list of each public key, in the same order.
+    #[serde(default)]
+    pub revocation_lists: Vec<PublishedRevocationList>,
+    /// Carrier's delivery receipt, with the same reference as the token.
+    #[serde(default)]
+    pub delivery_receipt: Option<DeliveryReceiptRequest>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeliveryReceiptRequest {
+    pub token: String,
+    /// Candidate carrier keys, in the same forms as `public_keys`.
+    pub public_keys: Vec<Value>,
+}
+
+fn key_strings(public_keys: Vec<Value>) -> Vec<String> {
+    public_keys
+        .into_iter()
+        .map(|pk| match pk {
+            Value::String(pk) => pk,
+            pk => pk.to_string(),
+        })
+        .collect()
+}
+
+async fn create_proof(
+    State(state): State<AppState>,
+    Json(request): Json<ProofRequest>,
+) -> Response {
+    if request.public_keys.is_empty() {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "At least one public key is required",
+        );
+    }
+    let public_keys = key_strings(request.public_keys);
+    let delivery_receipt = request.delivery_receipt.map(|receipt| DeliveryReceipt {
+        token: receipt.token.trim().to_string(),
+        public_keys: key_strings(receipt.public_keys),
+    });
+
+    let job = JobRecord::new(
+        request.token.trim().to_string(),
+        public_keys,
+        ProofOptions {
+            statements: request.statements,
+            revocation_lists: request.revocation_lists,
+            delivery_receipt,
+        },
+    );
+    let id = job.id.clone();
+    if let Err(e) = state.store.insert(job) {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    }
+
+    if state.queue.try_send(id.clone()).is_err() {
+        state.store.remove(&id);
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "The proving queue is full");
+    }
+
+    match state.store.response(&id) {
+        Some(response) => (StatusCode::ACCEPTED, Json(response)).into_response(),
+        None => error_response(StatusCode::INTERNAL_SERVER_ERROR, "Job disappeared"),
+    }
+}
+
+async fn get_proof(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    // Job IDs are used as file names, so only accept well-formed ones.
+    if Uuid::parse_str(&id).is_err() {
+        return error_response(StatusCode::NOT_FOUND, format!("Unknown job {id}"));
+    }
+    match state.store.response(&id) {
+        Some(response) => Json(response).into_response(),
+        None => error_response(StatusCode::NOT_FOUND, format!("Unknown job {id}")),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct VerifyRequest {
+    /// Base64 encoded receipt, as returned by `GET /proofs/{id}`.
+    pub receipt: String,
+    /// Current published revocation lists that the receipt must be up to
+    /// date with.
+    #[serde(default)]
+    pub revocation_lists: Vec<PublishedRevocationList>,
+    /// Trusted public keys of the issuers to check the candidate keys and
+    /// their validity windows against, or none to skip the check.
+    #[serde(default)]
+    pub key_set: Vec<String>,
+    /// Rotation records to apply to the windows of `key_set` first.
+    #[serde(default)]
+    pub rotations: Vec<String>,
+}
+
+async fn verify(Json(request): Json<VerifyRequest>) -> Response {
+    let bytes = match BASE64_STANDARD.decode(request.receipt.trim()) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Receipt is not valid base64: {e}"),
+            )
+        }
+    };
+    let key_set = match apply_rotations(&request.key_set, &request.rotations) {
+        Ok(key_set) => key_set,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let report = tokio::task::spawn_blocking(move || {
+        let mut report = VerificationReport::from_receipt_bytes(&bytes);
+        report.check_revocation_lists(&request.revocation_lists);
+        if !key_set.is_empty() {
+            report.check_key_validity(&key_set);
+        }
+        report
+    })
+    .await;
+    match report {
+        Ok(report) => Json(report).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::report::test::{fake_receipt, PK};
+    use crate::report::VerificationStatus;
+    use jwt_core::{CustomClaims, PublicOutput};
+
+    fn temp_state_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("zkpod-server-test-{}", Uuid::new_v4()))
+    }
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_store_requeues_running_jobs() {
+        let dir = temp_state_dir();
+        let store = JobStore::open(&dir).unwrap();
+        let job = JobRecord::new(
+            "token".to_string(),
+            vec![PK.to_string()],
+            ProofOptions::default(),
+        );
+        let id = job.id.clone();
+        store.insert(job).unwrap();
+        store
+            .update(&id, |job| job.status = JobStatus::Running)
+            .unwrap();
+
+        let reopened = JobStore::open(&dir).unwrap();
+        assert_eq!(reopened.get(&id).unwrap().status, JobStatus::Queued);
+        assert_eq!(reopened.queued(), vec![id]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_proof() {
+        let dir = temp_state_dir();
+        let (sender, mut receiver) = mpsc::channel(1);
+        let state = AppState {
+            store: Arc::new(JobStore::open(&dir).unwrap()),
+            queue: sender,
+        };
+
+        let request = ProofRequest {
+            token: "token".to_string(),
+            public_keys: vec![serde_json::from_str(PK).unwrap()],
+            statements: Statements::default(),
+            revocation_lists: Vec::new(),
+            delivery_receipt: None,
+        };
+        let response = create_proof(State(state.clone()), Json(request)).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let created = body_json(response).await;
+        assert_eq!(created["status"], "queued");
+        assert!(created.get("token").is_none());
+
+        let id = created["id"].as_str().unwrap().to_string();
+        assert_eq!(receiver.recv().await, Some(id.clone()));
+
+        let response = get_proof(State(state.clone()), Path(id)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get_proof(State(state), Path("../secret".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let response = verify(Json(VerifyRequest {
+            revocation_lists: Vec::new(),
+            key_set: Vec::new(),
+            rotations: Vec::new(),
+            receipt: "not base64!".to_string(),
+        }))
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let output = PublicOutput {
+            pks: vec![PK.to_string()],
+            claims: CustomClaims::new(),
+            hidden_claims: 0,
+            hidden_line_item_fields: 0,
+            buckets: Vec::new(),
+            unit_price_bands: Vec::new(),
+            date_windows: Vec::new(),
+            revocation: Vec::new(),
+            delivery: None,
+            audit: None,
+        };
+        let receipt = serialize_receipt(&fake_receipt(&output)).unwrap();
+        let response = verify(Json(VerifyRequest {
+            receipt: BASE64_STANDARD.encode(receipt),
+            revocation_lists: Vec::new(),
+            key_set: Vec::new(),
+            rotations: Vec::new(),
+        }))
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report: VerificationReport = serde_json::from_value(body_json(response).await).unwrap();
+        assert_eq!(report.status, VerificationStatus::Verified);
+    }
+}
@@ -1,3 +1,31 @@
 fn main() {
+    warn_about_unpatched_crypto();
     risc0_build::embed_methods();
 }
+
+/// The guest patches `rsa` and `sha2` with RISC Zero's accelerated forks,
+/// but Cargo ignores a patch whose version differs from one already in the
+/// lock file, and then builds the guest with the slow software versions.
+fn warn_about_unpatched_crypto() {
+    const LOCK_FILE: &str = "guest/Cargo.lock";
+    println!("cargo:rerun-if-changed={LOCK_FILE}");
+    let Ok(lock) = std::fs::read_to_string(LOCK_FILE) else {
+        return;
+    };
+    for package in lock.split("[[package]]") {
+        let field = |name: &str| {
+            package
+                .lines()
+                .find_map(|line| line.strip_prefix(&format!("{name} = \"")))
+                .and_then(|value| value.strip_suffix('"'))
+        };
+        let (Some(name), Some(source)) = (field("name"), field("source")) else {
+            continue;
+        };
+        if ["rsa", "sha2"].contains(&name) && source.starts_with("registry+") {
+            println!(
+                "cargo:warning=methods/guest/Cargo.lock pins {name} from crates.io, so the guest does not use the accelerated fork; run `cargo update -p {name}` in methods/guest"
+            );
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// The guest as it was before the prover passed it the index of the signing
+// key: it parses each candidate JWK and tries the keys in turn until one
+// validates the token.  It is only run by `zkpod cycles`, as the baseline
+// that the cycle counts of the main guest are compared with, and is never
+// proved.
+
+use jwt_core::{PublicOutput, Statements, Validator};
+use risc0_zkvm::guest::env;
+
+fn main() {
+    // read the token input
+    let token: String = env::read();
+    let num_public_keys: usize = env::read();
+    let mut pks: Vec<String> = Vec::new();
+
+    for _i in 0..num_public_keys {
+        pks.push(env::read());
+    }
+
+    let valid_token = pks
+        .iter()
+        .filter_map(|pk| {
+            pk.parse::<Validator>()
+                .ok()
+                .and_then(|validator| validator.validate_token_integrity(&token).ok())
+        })
+        .next()
+        .expect("failed to validate token with any key");
+
+    let public_output =
+        PublicOutput::new(pks, &valid_token.claims().custom, &Statements::default())
+            .expect("failed to commit the public claims");
+    env::commit(&public_output);
+}
@@ -16,33 +16,81 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use jwt_core::{PublicOutput, Validator};
+use jwt_core::{
+    check_key_set, check_revocation, AuditCiphertext, AuditSeed, CompactKey, DeliveryReceipt,
+    NonMembershipProof, PublicOutput, Statements,
+};
 use risc0_zkvm::guest::env;
 
 fn main() {
     // read the token input
     let token: String = env::read();
     let num_public_keys: usize = env::read();
-    let mut pks: Vec<String> = Vec::new();
+    let mut keys: Vec<CompactKey> = Vec::new();
 
     for _i in 0..num_public_keys {
-        pks.push(env::read());
+        keys.push(env::read());
     }
 
-    let valid_token = pks
-        .iter()
-        .filter_map(|pk| {
-            pk.parse::<Validator>().ok().and_then(|validator| {
-                let status = validator.validate_token_integrity(&token).ok();
-                status
-            })
+    // Junk or repeated keys would make the set look larger than it is
+    check_key_set(&keys).expect("candidate keys are not a canonical key set");
+
+    // Index of the signing key, so that only its signature is checked
+    let signer: usize = env::read();
+
+    // Statements to prove about the private claims
+    let statements: Statements = env::read();
+
+    // Proof that the token is not on its issuer's revocation list
+    let non_membership: Option<NonMembershipProof> = env::read();
+
+    // The carrier's receipt confirming delivery of the invoiced goods
+    let delivery_receipt: Option<DeliveryReceipt> = env::read();
+
+    // Randomness for encrypting the claims to the auditor, if there is one
+    let audit_seed: Option<AuditSeed> = env::read();
+
+    // The signing key must also have been valid when the token was issued
+    let valid_token = keys
+        .get(signer)
+        .and_then(|key| key.validator().ok())
+        .expect("no valid public key at the signing key index")
+        .validate_token_integrity(&token)
+        .expect("failed to validate token with the signing key");
+
+    // The keys are committed as JWKs
+    let pks: Vec<String> = keys.iter().map(CompactKey::to_jwk).collect();
+
+    let revocation = check_revocation(
+        &token,
+        &pks,
+        signer,
+        &statements.revocation_lists,
+        non_membership.as_ref(),
+    )
+    .expect("failed to prove the token is not revoked");
+
+    let claims = &valid_token.claims().custom;
+    let thumbprints: Vec<String> = keys.iter().map(CompactKey::thumbprint).collect();
+    let delivery = delivery_receipt
+        .map(|receipt| receipt.confirm(&token, claims, &thumbprints))
+        .transpose()
+        .expect("failed to confirm delivery");
+
+    let audit = statements
+        .auditor_key
+        .as_ref()
+        .map(|auditor_key| {
+            let seed = audit_seed.expect("missing randomness for the audit ciphertext");
+            AuditCiphertext::encrypt(claims, auditor_key, seed)
         })
-        .next()
-        .expect("failed to validate token with any key");
+        .transpose()
+        .expect("failed to encrypt the claims to the auditor");
 
-    let public_output = PublicOutput {
-        pks,
-        claims: valid_token.claims().custom.get_public_claims(),
-    };
+    let mut public_output = PublicOutput::new(pks, claims, &statements)
+        .expect("failed to prove statements about the claims");
+    public_output.revocation = revocation;
+    public_output.delivery = delivery;
+    public_output.audit = audit;
     env::commit(&public_output);
 }